@@ -0,0 +1,40 @@
+//! runtime of the discrete-log solvers as the subgroup order q grows
+//! run with `cargo bench --bench dlog_scaling`
+
+use std::time::{Duration, Instant};
+
+use num_bigint::BigUint;
+use zkp_chaum_pedersen::{
+    dlog,
+    paramgen::{self, ParamGenConfig},
+    ZKP,
+};
+
+const SAMPLES: u32 = 3;
+
+fn time_solver(
+    solver: fn(&BigUint, &BigUint, &BigUint, &BigUint) -> Option<BigUint>,
+    zkp: &ZKP,
+) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..SAMPLES {
+        let x = ZKP::generate_random_number(&zkp.order);
+        let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+        let start = Instant::now();
+        let recovered = solver(&zkp.alpha, &y, &zkp.prime, &zkp.order);
+        total += start.elapsed();
+        assert_eq!(recovered, Some(x));
+    }
+    total / SAMPLES
+}
+
+fn main() {
+    println!("{:>6} {:>14} {:>14}", "q bits", "bsgs", "rho");
+    for q_bits in (16..=40).step_by(4) {
+        let config = ParamGenConfig { p_bits: 128, q_bits, rounds: 20, safe_prime: false };
+        let zkp = paramgen::generate(&config);
+        let bsgs = time_solver(dlog::baby_step_giant_step, &zkp);
+        let rho = time_solver(dlog::pollard_rho, &zkp);
+        println!("{:>6} {:>14?} {:>14?}", q_bits, bsgs, rho);
+    }
+}
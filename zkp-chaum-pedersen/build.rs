@@ -1,10 +1,26 @@
 fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        cbindgen::Builder::new()
+            .with_config(cbindgen::Config::from_file("cbindgen.toml").unwrap())
+            .with_src("src/ffi.rs")
+            .generate()
+            .unwrap()
+            .write_to_file("include/zkp_chaum_pedersen.h");
+    }
+
+    // the generated src/zkp_*.rs files are only needed by the gRPC binaries
+    if std::env::var_os("CARGO_FEATURE_GRPC").is_none() {
+        return;
+    }
     tonic_build::configure()
         .build_server(true)
         .out_dir("src/") // you can change the generated code's location
         .compile(
-            &["proto/zkp_auth.proto"],
+            &["proto/zkp_auth.proto", "proto/zkp_dkg.proto"],
             &["proto/"], // specify the root location to search proto dependencies
         )
         .unwrap();
-}
\ No newline at end of file
+}
@@ -0,0 +1,103 @@
+#ifndef ZKP_CHAUM_PEDERSEN_H
+#define ZKP_CHAUM_PEDERSEN_H
+
+/* generated from src/ffi.rs by cbindgen when building with --features ffi, do not edit */
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef enum ZkpStatus {
+  ZKP_STATUS_OK = 0,
+  // the proof did not verify
+  ZKP_STATUS_REJECTED = 1,
+  ZKP_STATUS_NULL_POINTER = 2,
+  // a string argument is not UTF-8
+  ZKP_STATUS_INVALID_STRING = 3,
+  ZKP_STATUS_UNKNOWN_GROUP = 4,
+  // parameters that do not parse or do not form a valid group
+  ZKP_STATUS_INVALID_PARAMETERS = 5,
+  // a challenge that is not below q
+  ZKP_STATUS_INVALID_CHALLENGE = 6,
+  ZKP_STATUS_BUFFER_TOO_SMALL = 7,
+} ZkpStatus;
+
+// the k behind (r1, r2) = (alpha^k, beta^k) of one login, it never leaves the handle
+typedef struct ZkpCommitment ZkpCommitment;
+
+// group parameters (p, q, alpha, beta)
+typedef struct ZkpContext ZkpContext;
+
+// the prover's x
+typedef struct ZkpSecret ZkpSecret;
+
+// the 1024-bit group of `ZKP::get_constants`
+enum ZkpStatus zkp_context_new_default(struct ZkpContext **out);
+
+// one of the groups of `groups::GROUPS`, e.g. "toy-23"
+enum ZkpStatus zkp_context_new_group(const char *name, struct ZkpContext **out);
+
+// parameters in the text format of `params::to_string` or as PKCS#3 / X9.42 PEM
+enum ZkpStatus zkp_context_new_params(const char *text, struct ZkpContext **out);
+
+void zkp_context_free(struct ZkpContext *context);
+
+// bytes of p, the largest y1, y2, r1 or r2
+size_t zkp_element_size(const struct ZkpContext *context);
+
+// bytes of q, the largest c or s
+size_t zkp_scalar_size(const struct ZkpContext *context);
+
+// x from the password bytes, as the command line client derives it
+enum ZkpStatus zkp_secret_derive(const uint8_t *password,
+                                 size_t password_len,
+                                 struct ZkpSecret **out);
+
+void zkp_secret_free(struct ZkpSecret *secret);
+
+// (y1, y2) = (alpha^x, beta^x) for `RegisterRequest`
+enum ZkpStatus zkp_register(const struct ZkpContext *context,
+                            const struct ZkpSecret *secret,
+                            uint8_t *y1,
+                            size_t *y1_len,
+                            uint8_t *y2,
+                            size_t *y2_len);
+
+// fresh k < q and (r1, r2) for `AuthenticationChallengeRequest`
+// the commitment handle is only created if both values fit their buffers
+enum ZkpStatus zkp_commit(const struct ZkpContext *context,
+                          struct ZkpCommitment **out,
+                          uint8_t *r1,
+                          size_t *r1_len,
+                          uint8_t *r2,
+                          size_t *r2_len);
+
+void zkp_commitment_free(struct ZkpCommitment *commitment);
+
+// the verifier's random c < q
+enum ZkpStatus zkp_challenge(const struct ZkpContext *context, uint8_t *c, size_t *c_len);
+
+// s = k - c * x mod q for `AuthenticationAnswerRequest`
+enum ZkpStatus zkp_respond(const struct ZkpContext *context,
+                           const struct ZkpCommitment *commitment,
+                           const struct ZkpSecret *secret,
+                           const uint8_t *c,
+                           size_t c_len,
+                           uint8_t *s,
+                           size_t *s_len);
+
+// `ZKP_STATUS_OK` if r1 = alpha^s y1^c and r2 = beta^s y2^c, `ZKP_STATUS_REJECTED` otherwise
+enum ZkpStatus zkp_verify(const struct ZkpContext *context,
+                          const uint8_t *y1,
+                          size_t y1_len,
+                          const uint8_t *y2,
+                          size_t y2_len,
+                          const uint8_t *r1,
+                          size_t r1_len,
+                          const uint8_t *r2,
+                          size_t r2_len,
+                          const uint8_t *c,
+                          size_t c_len,
+                          const uint8_t *s,
+                          size_t s_len);
+
+#endif /* ZKP_CHAUM_PEDERSEN_H */
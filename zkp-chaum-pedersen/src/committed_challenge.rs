@@ -0,0 +1,99 @@
+use alloc::{vec, vec::Vec};
+
+use num_bigint::BigUint;
+use rand::RngCore;
+
+use crate::{hash, ZKP};
+
+const COMMITMENT_DST: &[u8] = b"zkp-chaum-pedersen/committed-challenge";
+const NONCE_SIZE: usize = 32;
+const COMMITMENT_SIZE: usize = 32;
+
+/// challenge c and the nonce that opens its commitment
+/// the verifier sends H(c, nonce) before it sees (r1, r2), so it cannot pick c as a function of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeOpening {
+    pub c: BigUint,
+    pub nonce: Vec<u8>,
+}
+
+impl ChallengeOpening {
+    /// random c < q with a fresh nonce
+    #[cfg(feature = "std")]
+    pub fn generate(zkp: &ZKP) -> Self {
+        Self::generate_with_rng(zkp, &mut rand::thread_rng())
+    }
+
+    /// `generate` with the caller's RNG
+    pub fn generate_with_rng<R: RngCore + ?Sized>(zkp: &ZKP, rng: &mut R) -> Self {
+        let c = ZKP::generate_random_number_with_rng(&zkp.order, rng);
+        Self::with_challenge_and_rng(c, rng)
+    }
+
+    /// opening for a challenge chosen elsewhere, e.g. by the server's challenge RNG
+    #[cfg(feature = "std")]
+    pub fn with_challenge(c: BigUint) -> Self {
+        Self::with_challenge_and_rng(c, &mut rand::thread_rng())
+    }
+
+    /// `with_challenge` with the caller's RNG for the nonce
+    pub fn with_challenge_and_rng<R: RngCore + ?Sized>(c: BigUint, rng: &mut R) -> Self {
+        let mut nonce = vec![0u8; NONCE_SIZE];
+        rng.fill_bytes(&mut nonce);
+        ChallengeOpening { c, nonce }
+    }
+
+    /// H(c, nonce)
+    pub fn commitment(&self) -> Vec<u8> {
+        hash::expand(COMMITMENT_DST, &[&self.c.to_bytes_be(), &self.nonce], COMMITMENT_SIZE)
+    }
+
+    /// the prover's check before answering: c < q and H(c, nonce) matches the commitment
+    pub fn verify(&self, zkp: &ZKP, commitment: &[u8]) -> bool {
+        self.c < zkp.order && self.commitment() == commitment
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_toy_example() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let x = BigUint::from(6_u32);
+        let (y1, y2) = (BigUint::from(2_u32), BigUint::from(3_u32));
+
+        // verifier commits, prover commits, verifier opens
+        let opening = ChallengeOpening::generate(&zkp);
+        let commitment = opening.commitment();
+        let k = BigUint::from(7_u32);
+        let r1 = ZKP::exponentiate(&zkp.alpha, &k, &zkp.prime);
+        let r2 = ZKP::exponentiate(&zkp.beta, &k, &zkp.prime);
+
+        assert!(opening.verify(&zkp, &commitment));
+        let s = zkp.solve(&k, &opening.c, &x);
+        assert!(zkp.verify(&r1, &r2, &y1, &y2, &opening.c, &s));
+    }
+
+    #[test]
+    fn test_rejects_changed_challenge() {
+        let zkp = ZKP::default();
+        let opening = ChallengeOpening::generate(&zkp);
+        let commitment = opening.commitment();
+
+        let changed = ChallengeOpening { c: (&opening.c + 1u32) % &zkp.order, ..opening.clone() };
+        assert!(!changed.verify(&zkp, &commitment));
+        let renonced = ChallengeOpening { nonce: vec![0u8; NONCE_SIZE], ..opening.clone() };
+        assert!(!renonced.verify(&zkp, &commitment));
+
+        // c must be reduced, so c and c + q cannot both open
+        let unreduced = ChallengeOpening::with_challenge(&opening.c + &zkp.order);
+        assert!(!unreduced.verify(&zkp, &unreduced.commitment()));
+    }
+}
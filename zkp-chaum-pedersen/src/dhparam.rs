@@ -0,0 +1,283 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use num_bigint::BigUint;
+
+use crate::{hash, paramgen, params::{self, ParamError}, ZKP};
+
+const BETA_DST: &[u8] = b"zkp-chaum-pedersen/dhparam/beta";
+const PKCS3_LABEL: &str = "DH PARAMETERS";
+const X942_LABEL: &str = "X9.42 DH PARAMETERS";
+
+const SEQUENCE: u8 = 0x30;
+const INTEGER: u8 = 0x02;
+
+/// PKCS#3 `DHParameter` carries only p and g, so q must be (p - 1) / 2
+/// X9.42 `DomainParameters` carries p, g and q
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Pkcs3,
+    X942,
+}
+
+impl Format {
+    fn label(self) -> &'static str {
+        match self {
+            Format::Pkcs3 => PKCS3_LABEL,
+            Format::X942 => X942_LABEL,
+        }
+    }
+}
+
+/// neither format has room for a second generator, so beta = H(p, q, alpha) in the order-q subgroup
+/// importing an exported group only gives back the same beta if it was derived this way
+pub fn derive_beta(prime: &BigUint, order: &BigUint, alpha: &BigUint) -> BigUint {
+    let zkp = ZKP {
+        prime: prime.clone(),
+        order: order.clone(),
+        alpha: alpha.clone(),
+        beta: alpha.clone(),
+    };
+    hash::hash_to_group(&zkp, BETA_DST, &[&prime.to_bytes_be(), &order.to_bytes_be(), &alpha.to_bytes_be()])
+}
+
+/// tag, length and contents of one DER element, and the rest of the input
+fn read_element(input: &[u8]) -> Result<(u8, &[u8], &[u8]), ParamError> {
+    let truncated = || ParamError::InvalidEncoding("truncated element");
+    let (&tag, rest) = input.split_first().ok_or_else(truncated)?;
+    let (&first, rest) = rest.split_first().ok_or_else(truncated)?;
+
+    let (length, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return Err(ParamError::InvalidEncoding("unsupported length"));
+        }
+        let (bytes, rest) = rest.split_at(count);
+        if bytes[0] == 0 {
+            return Err(ParamError::InvalidEncoding("length is not minimally encoded"));
+        }
+        let length = bytes.iter().fold(0usize, |length, byte| (length << 8) | *byte as usize);
+        if length < 0x80 {
+            return Err(ParamError::InvalidEncoding("length is not minimally encoded"));
+        }
+        (length, rest)
+    };
+
+    if rest.len() < length {
+        return Err(truncated());
+    }
+    let (contents, rest) = rest.split_at(length);
+    Ok((tag, contents, rest))
+}
+
+/// a non-negative, minimally encoded INTEGER
+fn read_integer(input: &[u8]) -> Result<(BigUint, &[u8]), ParamError> {
+    let (tag, contents, rest) = read_element(input)?;
+    if tag != INTEGER || contents.is_empty() {
+        return Err(ParamError::InvalidEncoding("expected an INTEGER"));
+    }
+    if contents[0] & 0x80 != 0 {
+        return Err(ParamError::InvalidEncoding("negative INTEGER"));
+    }
+    if contents.len() > 1 && contents[0] == 0 && contents[1] & 0x80 == 0 {
+        return Err(ParamError::InvalidEncoding("INTEGER is not minimally encoded"));
+    }
+    Ok((BigUint::from_bytes_be(contents), rest))
+}
+
+fn write_element(tag: u8, contents: &[u8], out: &mut Vec<u8>) {
+    out.push(tag);
+    let length = contents.len();
+    if length < 0x80 {
+        out.push(length as u8);
+    } else {
+        let bytes: Vec<u8> = length.to_be_bytes().into_iter().skip_while(|byte| *byte == 0).collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend_from_slice(&bytes);
+    }
+    out.extend_from_slice(contents);
+}
+
+fn write_integer(value: &BigUint, out: &mut Vec<u8>) {
+    let mut contents = value.to_bytes_be();
+    if contents[0] & 0x80 != 0 {
+        contents.insert(0, 0);
+    }
+    write_element(INTEGER, &contents, out);
+}
+
+/// validated group from p, g and q
+/// alpha is g if g has order q and g^2 otherwise, as `openssl dhparam` may output a generator of the whole group
+fn to_zkp(prime: BigUint, generator: BigUint, order: BigUint) -> Result<ZKP, ParamError> {
+    let one = BigUint::from(1u32);
+    if order <= one || prime <= order || (&prime - &one) % &order != BigUint::from(0u32) {
+        return Err(ParamError::InvalidGroup("q does not divide p - 1"));
+    }
+    let alpha = if ZKP::exponentiate(&generator, &order, &prime) == one {
+        generator
+    } else {
+        ZKP::exponentiate(&generator, &BigUint::from(2u32), &prime)
+    };
+    let beta = derive_beta(&prime, &order, &alpha);
+    let zkp = ZKP { prime, order, alpha, beta };
+    params::validate(&zkp, paramgen::DEFAULT_MILLER_RABIN_ROUNDS)?;
+    Ok(zkp)
+}
+
+/// parses and validates DER of the given format; trailing data is rejected
+pub fn from_der(der: &[u8], format: Format) -> Result<ZKP, ParamError> {
+    let (tag, contents, rest) = read_element(der)?;
+    if tag != SEQUENCE || !rest.is_empty() {
+        return Err(ParamError::InvalidEncoding("expected a single SEQUENCE"));
+    }
+    let (prime, contents) = read_integer(contents)?;
+    let (generator, contents) = read_integer(contents)?;
+
+    let (order, contents) = match format {
+        Format::Pkcs3 => {
+            // privateValueLength is optional and has no meaning here
+            let contents = match contents.is_empty() {
+                true => contents,
+                false => read_integer(contents)?.1,
+            };
+            ((&prime - 1u32) >> 1, contents)
+        }
+        Format::X942 => {
+            let (order, mut contents) = read_integer(contents)?;
+            // j is optional; validationParms are skipped, as their seed may come from SHA-1 (FIPS 186-2)
+            // rather than the SHA-256 generation `fips186` verifies, and the group is validated directly anyway
+            if contents.first() == Some(&INTEGER) {
+                contents = read_integer(contents)?.1;
+            }
+            if contents.first() == Some(&SEQUENCE) {
+                contents = read_element(contents)?.2;
+            }
+            (order, contents)
+        }
+    };
+    if !contents.is_empty() {
+        return Err(ParamError::InvalidEncoding("unexpected fields in SEQUENCE"));
+    }
+    to_zkp(prime, generator, order)
+}
+
+/// DER with g = alpha; beta is not encoded, see `derive_beta`
+pub fn to_der(zkp: &ZKP, format: Format) -> Vec<u8> {
+    let mut contents = Vec::new();
+    write_integer(&zkp.prime, &mut contents);
+    write_integer(&zkp.alpha, &mut contents);
+    if format == Format::X942 {
+        write_integer(&zkp.order, &mut contents);
+    }
+    let mut der = Vec::new();
+    write_element(SEQUENCE, &contents, &mut der);
+    der
+}
+
+/// PEM with the label of either format
+pub fn from_pem(text: &str) -> Result<ZKP, ParamError> {
+    let text = text.trim();
+    for format in [Format::X942, Format::Pkcs3] {
+        let begin = format!("-----BEGIN {}-----", format.label());
+        let end = format!("-----END {}-----", format.label());
+        if let Some(body) = text.strip_prefix(&begin).and_then(|body| body.strip_suffix(&end)) {
+            let encoded: String = body.split_whitespace().collect();
+            let der = STANDARD
+                .decode(encoded)
+                .map_err(|_| ParamError::InvalidEncoding("PEM body is not base64"))?;
+            return from_der(&der, format);
+        }
+    }
+    Err(ParamError::InvalidEncoding("not a DH PARAMETERS or X9.42 DH PARAMETERS PEM block"))
+}
+
+pub fn to_pem(zkp: &ZKP, format: Format) -> String {
+    let encoded = STANDARD.encode(to_der(zkp, format));
+    let mut pem = format!("-----BEGIN {}-----\n", format.label());
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", format.label()));
+    pem
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// `openssl dhparam 512`
+    const OPENSSL_DHPARAM: &str = "-----BEGIN DH PARAMETERS-----
+MEkCQQC7faFl1g5Qku2wwltEHkFXvf/nssJ3iwEGxL3lS/e3zxWUG9YdivLLiXdK
+ylAYRfI68zWYs+oqCXMuKNrFVyTXAgECAgF9
+-----END DH PARAMETERS-----";
+
+    /// `openssl genpkey -genparam -algorithm DHX -pkeyopt dh_paramgen_prime_len:1024 -pkeyopt dh_paramgen_subprime_len:160`
+    const OPENSSL_DHX: &str = "-----BEGIN X9.42 DH PARAMETERS-----
+MIIBOgKBgQCvJRvr8092b7PEWNALXCs0LhjUOjV6OzDlsgXMeQTTCz9nUSI9g4nt
+UxvGvz7sFgb06GHFLXZkTZjWvTf6kBj+egsgQvsYpuXHdpDOgScEv7uAOxmN1ulm
+b+PR2/wPXfXVGZgC/s8dmZacRT9/IP7z3UIqA56zHBn2u81shvixsQKBgGDLQ1a5
+TOaXYtFT5JuFJwVNKwQbO1uUMpqqYAeEWGdqyLDZAYy5FSse3QXTeV9NCQclrYK8
+RCAu0YO64Wtakozc8IFCo6R3hreAf9XZe2lnrRj85v3EmrHMKUZUgemC/PpRIfs5
+QMMgkPAsyxQfb5y4i8oRE/71R/ZPkfzfqN/VAhUA9MazyLw+lW1LQs2f26QiJ2lC
+haUwGgMVAOfpDGriIIYJrPRsGXIiZN5OIiAqAgEa
+-----END X9.42 DH PARAMETERS-----";
+
+    #[test]
+    fn test_import_openssl_dhparam() {
+        let zkp = from_pem(OPENSSL_DHPARAM).unwrap();
+        assert_eq!(zkp.prime.bits(), 512);
+        assert_eq!(zkp.order, (&zkp.prime - 1u32) >> 1);
+        assert_eq!(zkp.alpha, BigUint::from(2u32));
+        assert_eq!(from_pem(&to_pem(&zkp, Format::Pkcs3)).unwrap(), zkp);
+    }
+
+    #[test]
+    fn test_import_openssl_x942() {
+        let zkp = from_pem(OPENSSL_DHX).unwrap();
+        assert_eq!(zkp.prime.bits(), 1024);
+        assert_eq!(zkp.order.bits(), 160);
+        assert!(zkp.is_group_element(&zkp.beta));
+        assert_ne!(zkp.alpha, zkp.beta);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let zkp = ZKP::default();
+        let zkp = ZKP { beta: derive_beta(&zkp.prime, &zkp.order, &zkp.alpha), ..zkp };
+        assert_eq!(from_pem(&to_pem(&zkp, Format::X942)).unwrap(), zkp);
+        assert_eq!(from_der(&to_der(&zkp, Format::X942), Format::X942).unwrap(), zkp);
+
+        // the 1024-bit group has q of 160 bits, so PKCS#3 cannot carry it
+        let der = to_der(&zkp, Format::Pkcs3);
+        assert!(matches!(from_der(&der, Format::Pkcs3), Err(ParamError::InvalidGroup(_))));
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_der() {
+        let toy = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let der = to_der(&toy, Format::X942);
+        assert_eq!(der, [0x30, 0x09, 0x02, 0x01, 23, 0x02, 0x01, 4, 0x02, 0x01, 11]);
+        assert_eq!(from_der(&der, Format::X942).unwrap().alpha, toy.alpha);
+
+        let padded = [0x30, 0x0a, 0x02, 0x02, 0, 23, 0x02, 0x01, 4, 0x02, 0x01, 11];
+        assert!(matches!(from_der(&padded, Format::X942), Err(ParamError::InvalidEncoding(_))));
+        let negative = [0x30, 0x09, 0x02, 0x01, 0x97, 0x02, 0x01, 4, 0x02, 0x01, 11];
+        assert!(matches!(from_der(&negative, Format::X942), Err(ParamError::InvalidEncoding(_))));
+        let long_length = [0x30, 0x81, 0x09, 0x02, 0x01, 23, 0x02, 0x01, 4, 0x02, 0x01, 11];
+        assert!(matches!(from_der(&long_length, Format::X942), Err(ParamError::InvalidEncoding(_))));
+        let mut trailing = der.clone();
+        trailing.push(0);
+        assert!(matches!(from_der(&trailing, Format::X942), Err(ParamError::InvalidEncoding(_))));
+        assert!(matches!(from_der(&der[..6], Format::X942), Err(ParamError::InvalidEncoding(_))));
+
+        // 7 does not divide p - 1 = 22
+        let bad_order = [0x30, 0x09, 0x02, 0x01, 23, 0x02, 0x01, 4, 0x02, 0x01, 7];
+        assert!(matches!(from_der(&bad_order, Format::X942), Err(ParamError::InvalidGroup(_))));
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use num_bigint::BigUint;
+
+use crate::{
+    shamir::{Polynomial, Share},
+    threshold::{KeyShare, ThresholdKey},
+    vss, ZKP,
+};
+
+/// party `complainer` claims it got a bad share (or none) from `dealer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Complaint {
+    pub dealer: u32,
+    pub complainer: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DkgError {
+    NoQualifiedDealers,
+    InconsistentPublicKey,
+    /// the dealer already sent this party a deal
+    DuplicateDeal(u32),
+}
+
+/// one party of the Pedersen DKG (joint Feldman VSS)
+/// every party deals a random secret, the group key is the sum of the secrets of the qualified dealers
+#[derive(Debug)]
+pub struct Participant {
+    pub index: u32,
+    pub n: u32,
+    pub threshold: usize,
+    polynomial: Polynomial,
+    commitments: BTreeMap<u32, Vec<BigUint>>,
+    shares: BTreeMap<u32, Share>,
+    complaints: BTreeSet<Complaint>,
+    disqualified: BTreeSet<u32>,
+}
+
+impl Participant {
+    pub fn new(zkp: &ZKP, index: u32, n: u32, threshold: usize) -> Self {
+        assert!(index >= 1 && index <= n, "index must be in 1..=n");
+        assert!(threshold >= 1 && threshold <= n as usize, "threshold must be in 1..=n");
+        assert!(BigUint::from(n) < zkp.order, "n must be below q");
+        let secret = ZKP::generate_random_number(&zkp.order);
+        Participant {
+            index,
+            n,
+            threshold,
+            polynomial: Polynomial::random(&zkp.order, &secret, threshold),
+            commitments: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            complaints: BTreeSet::new(),
+            disqualified: BTreeSet::new(),
+        }
+    }
+
+    /// C_j = alpha^a_j of this party's own polynomial, broadcast to everyone
+    pub fn commitments(&self, zkp: &ZKP) -> Vec<BigUint> {
+        vss::feldman_commitments(zkp, &self.polynomial)
+    }
+
+    /// f(recipient), sent privately to the recipient
+    pub fn share_for(&self, zkp: &ZKP, recipient: u32) -> Share {
+        Share { index: recipient, value: self.polynomial.evaluate(&BigUint::from(recipient), &zkp.order) }
+    }
+
+    /// stores the dealer's commitments and this party's share
+    /// returns the complaint to broadcast if the share does not match the commitments
+    /// a second deal from the same dealer is rejected, commitments already known from a reveal must match
+    pub fn receive_deal(&mut self, zkp: &ZKP, dealer: u32, commitments: Vec<BigUint>, share: Share) -> Result<Option<Complaint>, DkgError> {
+        let complaint = Complaint { dealer, complainer: self.index };
+        if self.shares.contains_key(&dealer) || self.complaints.contains(&complaint) {
+            return Err(DkgError::DuplicateDeal(dealer));
+        }
+        let known = self.commitments.entry(dealer).or_insert_with(|| commitments.clone());
+        let valid = *known == commitments
+            && commitments.len() == self.threshold
+            && share.index == self.index
+            && vss::feldman_verify(zkp, &commitments, &share);
+        if valid {
+            self.shares.insert(dealer, share);
+            Ok(None)
+        } else {
+            self.complaints.insert(complaint);
+            Ok(Some(complaint))
+        }
+    }
+
+    pub fn has_deal(&self, dealer: u32) -> bool {
+        self.commitments.contains_key(&dealer)
+    }
+
+    /// complaints for dealers this party never heard from
+    pub fn missing_deals(&mut self) -> Vec<Complaint> {
+        let missing: Vec<Complaint> = (1..=self.n)
+            .filter(|dealer| !self.commitments.contains_key(dealer))
+            .map(|dealer| Complaint { dealer, complainer: self.index })
+            .collect();
+        self.complaints.extend(missing.iter().copied());
+        missing
+    }
+
+    /// remembers a complaint broadcast by another party
+    pub fn record_complaint(&mut self, complaint: Complaint) {
+        self.complaints.insert(complaint);
+    }
+
+    /// dealer side: reveals the complainer's share in public
+    pub fn answer_complaint(&self, zkp: &ZKP, complaint: &Complaint) -> (Vec<BigUint>, Share) {
+        (self.commitments(zkp), self.share_for(zkp, complaint.complainer))
+    }
+
+    /// everyone checks the revealed share against the dealer's commitments
+    /// a valid answer clears the complaint (and fixes the complainer's share), anything else disqualifies the dealer
+    pub fn resolve_complaint(&mut self, zkp: &ZKP, complaint: &Complaint, commitments: &[BigUint], revealed: &Share) {
+        let known = self.commitments.entry(complaint.dealer).or_insert_with(|| commitments.to_vec());
+        let valid = known.as_slice() == commitments
+            && known.len() == self.threshold
+            && revealed.index == complaint.complainer
+            && vss::feldman_verify(zkp, commitments, revealed);
+
+        if !valid {
+            self.disqualified.insert(complaint.dealer);
+            return;
+        }
+        self.complaints.remove(complaint);
+        if complaint.complainer == self.index {
+            self.shares.insert(complaint.dealer, revealed.clone());
+        }
+    }
+
+    /// dealers without open complaints whose commitments and share this party holds
+    pub fn qualified(&self) -> Vec<u32> {
+        (1..=self.n)
+            .filter(|dealer| !self.disqualified.contains(dealer))
+            .filter(|dealer| !self.complaints.iter().any(|complaint| complaint.dealer == *dealer))
+            .filter(|dealer| self.commitments.contains_key(dealer) && self.shares.contains_key(dealer))
+            .collect()
+    }
+
+    /// x_i = sum of the shares from qualified dealers, y = prod C_0 of the qualified dealers
+    /// y_j = prod over qualified dealers of their commitment to f(j)
+    pub fn finalize(&self, zkp: &ZKP) -> Result<(ThresholdKey, KeyShare), DkgError> {
+        let qualified = self.qualified();
+        if qualified.is_empty() {
+            return Err(DkgError::NoQualifiedDealers);
+        }
+
+        let mut x = BigUint::from(0u32);
+        let mut y = BigUint::from(1u32);
+        for dealer in &qualified {
+            x = (x + &self.shares[dealer].value) % &zkp.order;
+            y = (y * &self.commitments[dealer][0]) % &zkp.prime;
+        }
+
+        let public_shares = (1..=self.n)
+            .map(|index| {
+                let y_j = qualified.iter().fold(BigUint::from(1u32), |acc, dealer| {
+                    (acc * vss::commitment_at(zkp, &self.commitments[dealer], index)) % &zkp.prime
+                });
+                (index, y_j)
+            })
+            .collect();
+
+        let key = ThresholdKey { y, threshold: self.threshold, public_shares };
+        Ok((key, KeyShare { index: self.index, x }))
+    }
+}
+
+/// runs all n parties in one process with honest message delivery
+/// returns the joint key and every party's share
+pub fn run_in_process(zkp: &ZKP, n: u32, threshold: usize) -> Result<(ThresholdKey, Vec<KeyShare>), DkgError> {
+    let mut participants: Vec<Participant> = (1..=n)
+        .map(|index| Participant::new(zkp, index, n, threshold))
+        .collect();
+    run_rounds(zkp, &mut participants, |_, _, share| share)
+}
+
+/// deal, complain, answer and finalize; `deliver` may tamper with the share dealer -> recipient
+fn run_rounds<F>(zkp: &ZKP, participants: &mut [Participant], deliver: F) -> Result<(ThresholdKey, Vec<KeyShare>), DkgError>
+where
+    F: Fn(u32, u32, Share) -> Share,
+{
+    let deals: Vec<(u32, Vec<BigUint>, Vec<Share>)> = participants
+        .iter()
+        .map(|dealer| {
+            let shares = participants.iter().map(|recipient| dealer.share_for(zkp, recipient.index)).collect();
+            (dealer.index, dealer.commitments(zkp), shares)
+        })
+        .collect();
+
+    let mut complaints = Vec::new();
+    for (dealer, commitments, shares) in deals {
+        for (recipient, share) in participants.iter_mut().zip(shares) {
+            let share = deliver(dealer, recipient.index, share);
+            complaints.extend(recipient.receive_deal(zkp, dealer, commitments.clone(), share)?);
+        }
+    }
+
+    for complaint in &complaints {
+        participants.iter_mut().for_each(|participant| participant.record_complaint(*complaint));
+    }
+    for complaint in &complaints {
+        let dealer = &participants[complaint.dealer as usize - 1];
+        let (commitments, revealed) = dealer.answer_complaint(zkp, complaint);
+        participants
+            .iter_mut()
+            .for_each(|participant| participant.resolve_complaint(zkp, complaint, &commitments, &revealed));
+    }
+
+    let mut results = participants
+        .iter()
+        .map(|participant| participant.finalize(zkp))
+        .collect::<Result<Vec<_>, _>>()?;
+    if results.windows(2).any(|pair| pair[0].0 != pair[1].0) {
+        return Err(DkgError::InconsistentPublicKey);
+    }
+    let key = results[0].0.clone();
+    let shares = results.drain(..).map(|(_, share)| share).collect();
+    Ok((key, shares))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{elgamal, shamir, threshold};
+
+    #[test]
+    fn test_dkg_in_process_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let (key, shares) = run_in_process(&zkp, 4, 3).unwrap();
+
+        // the joint secret exists only in shares, but y = alpha^x for their interpolation
+        let plain: Vec<Share> = shares.iter().map(|share| Share { index: share.index, value: share.x.clone() }).collect();
+        let x = shamir::reconstruct(&zkp.order, &plain[..3]).unwrap();
+        assert_eq!(ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime), key.y);
+
+        let m = ZKP::exponentiate(&zkp.alpha, &BigUint::from(7_u32), &zkp.prime);
+        let ciphertext = elgamal::encrypt(&zkp, &key.y, &m);
+        let partials: Vec<_> = shares[1..]
+            .iter()
+            .map(|share| threshold::partial_decrypt(&zkp, share, &ciphertext))
+            .collect();
+        assert_eq!(threshold::combine(&zkp, &key, &ciphertext, &partials), Ok(m));
+    }
+
+    #[test]
+    fn test_complaint_against_bad_share_is_answered() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let mut participants: Vec<Participant> = (1..=3).map(|index| Participant::new(&zkp, index, 3, 2)).collect();
+
+        // dealer 2 sends a wrong share to party 3 but answers the complaint honestly
+        let (key, shares) = run_rounds(&zkp, &mut participants, |dealer, recipient, share| {
+            if dealer == 2 && recipient == 3 {
+                Share { value: (share.value + 1u32) % 11u32, ..share }
+            } else {
+                share
+            }
+        })
+        .unwrap();
+
+        assert_eq!(participants[0].qualified(), vec![1, 2, 3]);
+        let plain: Vec<Share> = shares.iter().map(|share| Share { index: share.index, value: share.x.clone() }).collect();
+        let x = shamir::reconstruct(&zkp.order, &plain[1..]).unwrap();
+        assert_eq!(ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime), key.y);
+    }
+
+    #[test]
+    fn test_dealer_with_bad_answer_is_disqualified() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let mut participants: Vec<Participant> = (1..=3).map(|index| Participant::new(&zkp, index, 3, 2)).collect();
+        let commitments = participants[1].commitments(&zkp);
+        let complaint = Complaint { dealer: 2, complainer: 3 };
+        let bad_share = Share { index: 1, value: BigUint::from(0_u32) };
+
+        participants[0].record_complaint(complaint);
+        participants[0].resolve_complaint(&zkp, &complaint, &commitments, &bad_share);
+        assert!(!participants[0].qualified().contains(&2));
+    }
+
+    #[test]
+    fn test_repeated_deal_is_rejected() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let mut participants: Vec<Participant> = (1..=2).map(|index| Participant::new(&zkp, index, 2, 2)).collect();
+        let share = participants[1].share_for(&zkp, 1);
+        let commitments = participants[1].commitments(&zkp);
+        assert_eq!(participants[0].receive_deal(&zkp, 2, commitments, share.clone()), Ok(None));
+
+        // a second deal with another polynomial must not replace the accepted share
+        let other = Participant::new(&zkp, 2, 2, 2);
+        let result = participants[0].receive_deal(&zkp, 2, other.commitments(&zkp), other.share_for(&zkp, 1));
+        assert_eq!(result, Err(DkgError::DuplicateDeal(2)));
+        assert_eq!(participants[0].shares[&2], share);
+    }
+}
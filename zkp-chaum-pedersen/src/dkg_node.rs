@@ -0,0 +1,394 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use num_bigint::BigUint;
+use prost::Message;
+use rand::RngCore;
+use tonic::{transport::{Channel, Server}, Code, Request, Response, Status};
+
+use zkp_chaum_pedersen::{
+    dkg::{Complaint, Participant},
+    hash, schnorr,
+    shamir::Share,
+    threshold::{KeyShare, ThresholdKey},
+    ZKP,
+};
+
+pub mod zkp_dkg {
+    include!("./zkp_dkg.rs");
+}
+
+use zkp_dkg::{dkg_client::DkgClient, dkg_server::{Dkg, DkgServer}, ComplaintRequest, ComplaintResponse, DealRequest, DealResponse, PublicKeyRequest, PublicKeyResponse, RevealRequest, RevealResponse, Signature};
+
+const PHASE_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const SIGNATURE_DST: &[u8] = b"zkp-chaum-pedersen/dkg-node/signature";
+const SHARE_PAD_DST: &[u8] = b"zkp-chaum-pedersen/dkg-node/share-pad";
+const IDENTITY_VAR: &str = "DKG_IDENTITY";
+
+pub struct DkgImpl {
+    pub zkp: ZKP,
+    /// this party's long-term secret, its public key is `keys[index - 1]`
+    pub identity: BigUint,
+    /// public key of every party, the only way a request is tied to an index
+    pub keys: Vec<BigUint>,
+    pub participant: Mutex<Participant>,
+    pub peers: Mutex<Vec<DkgClient<Channel>>>,
+    pub complaints: Mutex<Vec<Complaint>>,
+    pub result: Mutex<Option<(ThresholdKey, KeyShare)>>,
+}
+
+fn to_biguints(values: &[Vec<u8>]) -> Vec<BigUint> {
+    values.iter().map(|value| BigUint::from_bytes_be(value)).collect()
+}
+
+fn to_bytes(values: &[BigUint]) -> Vec<Vec<u8>> {
+    values.iter().map(|value| value.to_bytes_be()).collect()
+}
+
+/// what the sender signs: the rpc, the recipient and the request with its signature field empty
+fn signed_bytes<M: Message>(rpc: &str, recipient: u32, request: &M) -> Vec<u8> {
+    let mut bytes = SIGNATURE_DST.to_vec();
+    bytes.extend_from_slice(rpc.as_bytes());
+    bytes.extend_from_slice(&recipient.to_be_bytes());
+    bytes.extend_from_slice(&request.encode_to_vec());
+    bytes
+}
+
+/// H(y_dealer^x_recipient = y_recipient^x_dealer, nonce, dealer, recipient), as long as a share
+fn share_pad(zkp: &ZKP, shared: &BigUint, nonce: &[u8], dealer: u32, recipient: u32) -> Vec<u8> {
+    let size = zkp.order.bits().div_ceil(8) as usize;
+    let shared = shared.to_bytes_be();
+    hash::expand(SHARE_PAD_DST, &[&shared, nonce, &dealer.to_be_bytes(), &recipient.to_be_bytes()], size)
+}
+
+fn xor(pad: &[u8], data: &[u8]) -> Vec<u8> {
+    pad.iter().zip(data).map(|(a, b)| a ^ b).collect()
+}
+
+/// the helpers fail with a boxed Status, the handlers unbox it to return it
+impl DkgImpl {
+    fn key(&self, index: u32) -> Result<&BigUint, Box<Status>> {
+        index
+            .checked_sub(1)
+            .and_then(|position| self.keys.get(position as usize))
+            .ok_or_else(|| Box::new(Status::new(Code::InvalidArgument, format!("there is no party {}", index))))
+    }
+
+    fn sign<M: Message>(&self, rpc: &str, recipient: u32, request: &M) -> Option<Signature> {
+        let signature = schnorr::sign(&self.zkp, &self.identity, &signed_bytes(rpc, recipient, request));
+        Some(Signature { e: signature.e.to_bytes_be(), s: signature.s.to_bytes_be() })
+    }
+
+    /// the request must be signed by party `sender` for this party, `request` has its signature taken out
+    fn authenticate<M: Message>(&self, rpc: &str, sender: u32, request: &M, signature: Option<Signature>) -> Result<(), Box<Status>> {
+        let key = self.key(sender)?;
+        let index = self.participant.lock().unwrap().index;
+        let signature = signature.ok_or_else(|| Box::new(Status::new(Code::Unauthenticated, "request is not signed")))?;
+        let signature = schnorr::Signature {
+            e: BigUint::from_bytes_be(&signature.e),
+            s: BigUint::from_bytes_be(&signature.s),
+        };
+        if !schnorr::verify(&self.zkp, key, &signed_bytes(rpc, index, request), &signature) {
+            return Err(Box::new(Status::new(Code::Unauthenticated, format!("request is not signed by party {}", sender))));
+        }
+        Ok(())
+    }
+
+    /// f(recipient) padded to the size of q and encrypted to the recipient's key
+    fn encrypt_share(&self, share: &Share, nonce: &[u8]) -> Result<Vec<u8>, Box<Status>> {
+        let dealer = self.participant.lock().unwrap().index;
+        let shared = ZKP::exponentiate(self.key(share.index)?, &self.identity, &self.zkp.prime);
+        let pad = share_pad(&self.zkp, &shared, nonce, dealer, share.index);
+        let value = share.value.to_bytes_be();
+        let mut padded = vec![0u8; pad.len() - value.len()];
+        padded.extend_from_slice(&value);
+        Ok(xor(&pad, &padded))
+    }
+
+    fn decrypt_share(&self, dealer: u32, ciphertext: &[u8], nonce: &[u8]) -> Result<Share, Box<Status>> {
+        let index = self.participant.lock().unwrap().index;
+        let shared = ZKP::exponentiate(self.key(dealer)?, &self.identity, &self.zkp.prime);
+        let pad = share_pad(&self.zkp, &shared, nonce, dealer, index);
+        if ciphertext.len() != pad.len() {
+            return Err(Box::new(Status::new(Code::InvalidArgument, "encrypted share has the wrong length")));
+        }
+        Ok(Share { index, value: BigUint::from_bytes_be(&xor(&pad, ciphertext)) })
+    }
+}
+
+/// sends the reveal to every party, the dealer's answer to a complaint must be public
+async fn broadcast_reveal(peers: Vec<DkgClient<Channel>>, requests: Vec<RevealRequest>) {
+    for (mut peer, request) in peers.into_iter().zip(requests) {
+        if let Err(status) = peer.reveal(request).await {
+            println!("☒ Could not deliver reveal: {}", status.message());
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Dkg for DkgImpl {
+    async fn deal(&self, request: Request<DealRequest>) -> Result<Response<DealResponse>, Status> {
+        let mut request = request.into_inner();
+        let signature = request.signature.take();
+        self.authenticate("Deal", request.dealer, &request, signature).map_err(|status| *status)?;
+        println!("Processing Deal from party {}", request.dealer);
+
+        let share = self.decrypt_share(request.dealer, &request.share, &request.nonce).map_err(|status| *status)?;
+        let complaint = self.participant
+            .lock()
+            .unwrap()
+            .receive_deal(&self.zkp, request.dealer, to_biguints(&request.commitments), share)
+            .map_err(|_| Status::new(Code::AlreadyExists, format!("party {} already dealt", request.dealer)))?;
+
+        if let Some(complaint) = complaint {
+            println!("⚠ Share from party {} does not match its commitments.", request.dealer);
+            self.complaints.lock().unwrap().push(complaint);
+        }
+        Ok(Response::new(DealResponse { accepted: complaint.is_none() }))
+    }
+
+    /// only the complainer itself can complain, about the share it was dealt
+    async fn complain(&self, request: Request<ComplaintRequest>) -> Result<Response<ComplaintResponse>, Status> {
+        let mut request = request.into_inner();
+        let signature = request.signature.take();
+        self.authenticate("Complain", request.complainer, &request, signature).map_err(|status| *status)?;
+        self.key(request.dealer).map_err(|status| *status)?;
+        println!("Processing Complaint of party {} against party {}", request.complainer, request.dealer);
+
+        let complaint = Complaint { dealer: request.dealer, complainer: request.complainer };
+        let participant = &mut self.participant.lock().unwrap();
+        participant.record_complaint(complaint);
+
+        if complaint.dealer == participant.index {
+            let (commitments, share) = participant.answer_complaint(&self.zkp, &complaint);
+            let reveal = RevealRequest {
+                dealer: complaint.dealer,
+                complainer: complaint.complainer,
+                commitments: to_bytes(&commitments),
+                share: share.value.to_bytes_be(),
+                signature: None,
+            };
+            let requests = (1..=self.keys.len() as u32)
+                .map(|recipient| RevealRequest { signature: self.sign("Reveal", recipient, &reveal), ..reveal.clone() })
+                .collect();
+            let peers = self.peers.lock().unwrap().clone();
+            tokio::spawn(broadcast_reveal(peers, requests));
+        }
+        Ok(Response::new(ComplaintResponse {}))
+    }
+
+    async fn reveal(&self, request: Request<RevealRequest>) -> Result<Response<RevealResponse>, Status> {
+        let mut request = request.into_inner();
+        let signature = request.signature.take();
+        self.authenticate("Reveal", request.dealer, &request, signature).map_err(|status| *status)?;
+        self.key(request.complainer).map_err(|status| *status)?;
+        println!("Processing Reveal of party {} for party {}", request.dealer, request.complainer);
+
+        let complaint = Complaint { dealer: request.dealer, complainer: request.complainer };
+        let revealed = Share { index: request.complainer, value: BigUint::from_bytes_be(&request.share) };
+        self.participant
+            .lock()
+            .unwrap()
+            .resolve_complaint(&self.zkp, &complaint, &to_biguints(&request.commitments), &revealed);
+        Ok(Response::new(RevealResponse {}))
+    }
+
+    /// ready is false until this party has finalized, y and qualified are empty then
+    async fn public_key(&self, request: Request<PublicKeyRequest>) -> Result<Response<PublicKeyResponse>, Status> {
+        let requester = request.into_inner().requester;
+        self.key(requester).map_err(|status| *status)?;
+        let mut response = match self.result.lock().unwrap().as_ref() {
+            Some((key, _)) => PublicKeyResponse {
+                ready: true,
+                y: key.y.to_bytes_be(),
+                qualified: self.participant.lock().unwrap().qualified(),
+                signature: None,
+            },
+            None => PublicKeyResponse::default(),
+        };
+        response.signature = self.sign("PublicKey", requester, &response);
+        Ok(Response::new(response))
+    }
+}
+
+/// asks every party for its signed result until all are ready or the phase times out
+/// returns the parties that derived another qualified set or public key, or never answered
+async fn disagreeing_parties(node: &DkgImpl, peers: &mut [DkgClient<Channel>], y: &BigUint, qualified: &[u32]) -> Vec<u32> {
+    let index = node.participant.lock().unwrap().index;
+    let mut pending: Vec<u32> = (1..=peers.len() as u32).collect();
+    let mut disagreeing = Vec::new();
+    let started = Instant::now();
+    while !pending.is_empty() && started.elapsed() < PHASE_TIMEOUT {
+        let mut unanswered = Vec::new();
+        for party in pending {
+            let response = peers[party as usize - 1].public_key(PublicKeyRequest { requester: index }).await;
+            match response.map(Response::into_inner) {
+                Ok(mut response) if response.ready => {
+                    let signature = response.signature.take();
+                    let authentic = node.authenticate("PublicKey", party, &response, signature).is_ok();
+                    if !authentic || BigUint::from_bytes_be(&response.y) != *y || response.qualified != qualified {
+                        disagreeing.push(party);
+                    }
+                }
+                _ => unanswered.push(party),
+            }
+        }
+        pending = unanswered;
+        if !pending.is_empty() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+    disagreeing.extend(pending);
+    disagreeing.sort();
+    disagreeing
+}
+
+async fn connect(addr: &str) -> DkgClient<Channel> {
+    let started = Instant::now();
+    loop {
+        match DkgClient::connect(format!("http://{}", addr)).await {
+            Ok(client) => return client,
+            Err(_) if started.elapsed() < PHASE_TIMEOUT => tokio::time::sleep(POLL_INTERVAL).await,
+            Err(error) => panic!("could not connect to party at {}: {}", addr, error),
+        }
+    }
+}
+
+/// usage: dkg-node keygen
+///        DKG_IDENTITY=<secret> dkg-node <index> <threshold> <address of party 1>=<public key> ... <address of party n>=<public key>
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (alpha, beta, prime, order) = ZKP::get_constants();
+    let zkp = ZKP { prime, order, alpha, beta };
+
+    if args.first().map(String::as_str) == Some("keygen") {
+        let x = ZKP::generate_random_number(&(&zkp.order - 1u32)) + 1u32;
+        println!("☑ {}={}", IDENTITY_VAR, hex::encode(x.to_bytes_be()));
+        println!("☑ public key {}", hex::encode(ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime).to_bytes_be()));
+        return;
+    }
+    if args.len() < 3 {
+        println!("usage: dkg-node keygen");
+        println!("       {}=<secret> dkg-node <index> <threshold> <address of party 1>=<public key> ... <address of party n>=<public key>", IDENTITY_VAR);
+        return;
+    }
+    let index: u32 = args[0].parse().expect("index must be a number");
+    let threshold: usize = args[1].parse().expect("threshold must be a number");
+    let (addresses, keys): (Vec<String>, Vec<BigUint>) = args[2..]
+        .iter()
+        .map(|party| {
+            let (address, key) = party.split_once('=').expect("parties must be given as <address>=<public key>");
+            let key = BigUint::from_bytes_be(&hex::decode(key).expect("public key must be hex"));
+            assert!(zkp.is_group_element(&key), "public key of {} is not in the group", address);
+            (address.to_string(), key)
+        })
+        .unzip();
+    let n = addresses.len() as u32;
+
+    let identity = std::env::var(IDENTITY_VAR).expect("DKG_IDENTITY must hold this party's secret from dkg-node keygen");
+    let identity = BigUint::from_bytes_be(&hex::decode(identity).expect("DKG_IDENTITY must be hex"));
+    assert_eq!(
+        ZKP::exponentiate(&zkp.alpha, &identity, &zkp.prime),
+        keys[index as usize - 1],
+        "DKG_IDENTITY does not match the public key of party {}",
+        index
+    );
+
+    let participant = Participant::new(&zkp, index, n, threshold);
+    let node = Arc::new(DkgImpl {
+        zkp,
+        identity,
+        keys,
+        participant: Mutex::new(participant),
+        peers: Mutex::new(Vec::new()),
+        complaints: Mutex::new(Vec::new()),
+        result: Mutex::new(None),
+    });
+
+    let addr = addresses[index as usize - 1].clone();
+    println!("☑ Running DKG party {} of {} in {}.", index, n, addr);
+    let server = tokio::spawn(
+        Server::builder()
+            .add_service(DkgServer::from_arc(node.clone()))
+            .serve(addr.parse().expect("could not convert address")),
+    );
+
+    let mut peers = Vec::new();
+    for address in &addresses {
+        peers.push(connect(address).await);
+    }
+    *node.peers.lock().unwrap() = peers.clone();
+    println!("☑ Connected to all {} parties.", n);
+
+    // round 1: send every party its encrypted share together with the commitments
+    let (commitments, shares) = {
+        let participant = node.participant.lock().unwrap();
+        let shares: Vec<Share> = (1..=n).map(|recipient| participant.share_for(&node.zkp, recipient)).collect();
+        (participant.commitments(&node.zkp), shares)
+    };
+    for (peer, share) in peers.iter_mut().zip(shares) {
+        let mut nonce = vec![0u8; 16];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let mut request = DealRequest {
+            dealer: index,
+            commitments: to_bytes(&commitments),
+            share: node.encrypt_share(&share, &nonce).expect("could not encrypt share"),
+            nonce,
+            signature: None,
+        };
+        request.signature = node.sign("Deal", share.index, &request);
+        peer.deal(request).await.expect("could not send deal");
+    }
+
+    let started = Instant::now();
+    while started.elapsed() < PHASE_TIMEOUT {
+        if (1..=n).all(|dealer| node.participant.lock().unwrap().has_deal(dealer)) {
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    // round 2: broadcast complaints, dealers answer them with reveals
+    let mut complaints = node.complaints.lock().unwrap().clone();
+    complaints.extend(node.participant.lock().unwrap().missing_deals());
+    for complaint in complaints {
+        println!("⚠ Complaining against party {}.", complaint.dealer);
+        for (recipient, peer) in (1..=n).zip(peers.iter_mut()) {
+            let mut request = ComplaintRequest { dealer: complaint.dealer, complainer: complaint.complainer, signature: None };
+            request.signature = node.sign("Complain", recipient, &request);
+            if let Err(status) = peer.complain(request).await {
+                println!("☒ Could not deliver complaint: {}", status.message());
+            }
+        }
+    }
+    tokio::time::sleep(PHASE_TIMEOUT / 2).await;
+
+    // round 3: combine the shares of the qualified dealers
+    let result = node.participant.lock().unwrap().finalize(&node.zkp);
+    match result {
+        Ok((key, share)) => {
+            let qualified = node.participant.lock().unwrap().qualified();
+            let y = key.y.clone();
+            let public_share = key.public_share(share.index).unwrap().clone();
+            *node.result.lock().unwrap() = Some((key, share));
+
+            // round 4: every party must have derived the same qualified set and joint public key
+            let disagreeing = disagreeing_parties(&node, &mut peers, &y, &qualified).await;
+            if disagreeing.is_empty() {
+                println!("☑ Qualified dealers: {:?}", qualified);
+                println!("☑ Joint public key y = {}", hex::encode(y.to_bytes_be()));
+                println!("☑ Own public share y_{} = {}", index, hex::encode(public_share.to_bytes_be()));
+                println!("☑ All {} parties agree on the result.", n);
+            } else {
+                println!("☒ DKG failed: parties {:?} did not confirm qualified dealers {:?} and y = {}", disagreeing, qualified, hex::encode(y.to_bytes_be()));
+            }
+        }
+        Err(error) => println!("☒ DKG failed: {:?}", error),
+    }
+
+    server.await
+        .expect("server task panicked")
+        .expect("server failed");
+}
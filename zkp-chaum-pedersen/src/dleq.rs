@@ -0,0 +1,78 @@
+use alloc::vec::Vec;
+
+use num_bigint::BigUint;
+use rand::RngCore;
+
+use crate::{hash, ZKP};
+
+const DLEQ_DST: &[u8] = b"zkp-chaum-pedersen/dleq";
+
+/// non-interactive Chaum-Pedersen proof that log_alpha(y1) == log_beta(y2)
+/// the challenge is derived from the transcript (Fiat-Shamir) instead of sent by a verifier
+#[derive(Debug, Clone, PartialEq)]
+pub struct DleqProof {
+    pub r1: BigUint,
+    pub r2: BigUint,
+    pub s: BigUint,
+}
+
+/// same group as `zkp` with beta replaced, to prove log_alpha(y1) == log_base(y2)
+pub fn with_second_base(zkp: &ZKP, base: BigUint) -> ZKP {
+    ZKP {
+        prime: zkp.prime.clone(),
+        order: zkp.order.clone(),
+        alpha: zkp.alpha.clone(),
+        beta: base,
+    }
+}
+
+/// c = H(p, q, alpha, beta, y1, y2, r1, r2) mod q
+pub fn challenge(zkp: &ZKP, y1: &BigUint, y2: &BigUint, r1: &BigUint, r2: &BigUint) -> BigUint {
+    let parts = [&zkp.prime, &zkp.order, &zkp.alpha, &zkp.beta, y1, y2, r1, r2]
+        .map(|value| value.to_bytes_be());
+    let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+    hash::hash_to_scalar(zkp, DLEQ_DST, &parts)
+}
+
+/// proves y1 = alpha^x and y2 = beta^x for the bases of `zkp`
+#[cfg(feature = "std")]
+pub fn prove(zkp: &ZKP, x: &BigUint, y1: &BigUint, y2: &BigUint) -> DleqProof {
+    prove_with_rng(zkp, x, y1, y2, &mut rand::thread_rng())
+}
+
+/// `prove` with the caller's RNG for the nonce k
+pub fn prove_with_rng<R: RngCore + ?Sized>(zkp: &ZKP, x: &BigUint, y1: &BigUint, y2: &BigUint, rng: &mut R) -> DleqProof {
+    let (k, r1, r2) = zkp.commitment_with_rng(rng);
+    let c = challenge(zkp, y1, y2, &r1, &r2);
+    let s = zkp.solve(&k, &c, x);
+    DleqProof { r1, r2, s }
+}
+
+/// recomputes the challenge and checks the same relation as the interactive protocol
+pub fn verify(zkp: &ZKP, y1: &BigUint, y2: &BigUint, proof: &DleqProof) -> bool {
+    let c = challenge(zkp, y1, y2, &proof.r1, &proof.r2);
+    zkp.verify(&proof.r1, &proof.r2, y1, y2, &c, &proof.s)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_dleq_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+
+        let x = ZKP::generate_random_number(&zkp.order);
+        let y1 = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+        let y2 = ZKP::exponentiate(&zkp.beta, &x, &zkp.prime);
+
+        let proof = prove(&zkp, &x, &y1, &y2);
+        assert!(verify(&zkp, &y1, &y2, &proof));
+
+        let other = ZKP::generate_random_number(&zkp.order);
+        let y2_other = ZKP::exponentiate(&zkp.beta, &other, &zkp.prime);
+        assert!(!verify(&zkp, &y1, &y2_other, &proof));
+        assert!(!verify(&zkp, &y1, &y2_other, &prove(&zkp, &x, &y1, &y2_other)));
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+
+use crate::{number_theory, ZKP};
+
+/// x with g^x = y mod p for g of order n, in O(sqrt(n)) time and memory
+pub fn baby_step_giant_step(g: &BigUint, y: &BigUint, prime: &BigUint, order: &BigUint) -> Option<BigUint> {
+    let m = order.sqrt() + 1u32;
+
+    // baby steps: g^j for j < m
+    let mut table = HashMap::new();
+    let mut value = BigUint::from(1u32);
+    let mut j = BigUint::from(0u32);
+    while j < m {
+        table.entry(value.clone()).or_insert_with(|| j.clone());
+        value = (value * g) % prime;
+        j += 1u32;
+    }
+
+    // giant steps: y * g^(-m i) for i < m
+    let factor = number_theory::mod_inverse(&ZKP::exponentiate(g, &m, prime), prime)?;
+    let mut gamma = y % prime;
+    let mut i = BigUint::from(0u32);
+    while i < m {
+        if let Some(j) = table.get(&gamma) {
+            return Some((&i * &m + j) % order);
+        }
+        gamma = (gamma * &factor) % prime;
+        i += 1u32;
+    }
+    None
+}
+
+/// one step of the walk (z, a, b) with z = g^a y^b, split into three sets by z mod 3
+fn rho_step(z: &mut BigUint, a: &mut BigUint, b: &mut BigUint, g: &BigUint, y: &BigUint, prime: &BigUint, order: &BigUint) {
+    match (&*z % 3u32).to_u32_digits().first().copied().unwrap_or(0) {
+        0 => {
+            *z = (&*z * &*z) % prime;
+            *a = (&*a << 1) % order;
+            *b = (&*b << 1) % order;
+        }
+        1 => {
+            *z = (&*z * g) % prime;
+            *a = (&*a + 1u32) % order;
+        }
+        _ => {
+            *z = (&*z * y) % prime;
+            *b = (&*b + 1u32) % order;
+        }
+    }
+}
+
+/// x with g^x = y mod p for g of prime order n, in O(sqrt(n)) time and constant memory
+/// Floyd cycle finding from random starting points until a usable collision appears
+pub fn pollard_rho(g: &BigUint, y: &BigUint, prime: &BigUint, order: &BigUint) -> Option<BigUint> {
+    if ZKP::exponentiate(y, order, prime) != BigUint::from(1u32) {
+        return None;
+    }
+    for _ in 0..16 {
+        let mut a = ZKP::generate_random_number(order);
+        let mut b = ZKP::generate_random_number(order);
+        let mut z = (ZKP::exponentiate(g, &a, prime) * ZKP::exponentiate(y, &b, prime)) % prime;
+        let (mut z2, mut a2, mut b2) = (z.clone(), a.clone(), b.clone());
+
+        loop {
+            rho_step(&mut z, &mut a, &mut b, g, y, prime, order);
+            rho_step(&mut z2, &mut a2, &mut b2, g, y, prime, order);
+            rho_step(&mut z2, &mut a2, &mut b2, g, y, prime, order);
+            if z == z2 {
+                break;
+            }
+        }
+
+        // g^a y^b = g^a2 y^b2, so (b2 - b) x = a - a2 mod n
+        let denominator = (&b2 + order - &b) % order;
+        let numerator = (&a + order - &a2) % order;
+        if let Some(inverse) = number_theory::mod_inverse(&denominator, order) {
+            let x = (numerator * inverse) % order;
+            if ZKP::exponentiate(g, &x, prime) == *y {
+                return Some(x);
+            }
+        }
+    }
+    None
+}
+
+/// x with g^x = y mod p for g of composite order n = prod p_i^e_i
+/// solves in every subgroup of order p_i^e_i digit by digit and combines with the CRT,
+/// so the cost depends on the largest prime factor of n, not on n
+pub fn pohlig_hellman(g: &BigUint, y: &BigUint, prime: &BigUint, order: &BigUint) -> Option<BigUint> {
+    let one = BigUint::from(1u32);
+    let mut congruences = Vec::new();
+
+    for (factor, exponent) in number_theory::factorize(order) {
+        let prime_power = factor.pow(exponent);
+        let cofactor = order / &prime_power;
+        let g_i = ZKP::exponentiate(g, &cofactor, prime);
+        let y_i = ZKP::exponentiate(y, &cofactor, prime);
+
+        // generator of the order-p_i subgroup used for every digit
+        let gamma = ZKP::exponentiate(&g_i, &factor.pow(exponent - 1), prime);
+        let mut x_i = BigUint::from(0u32);
+        let mut factor_power = one.clone();
+        for k in 0..exponent {
+            let g_inverse = number_theory::mod_inverse(&ZKP::exponentiate(&g_i, &x_i, prime), prime)?;
+            let h = ZKP::exponentiate(&((&y_i * g_inverse) % prime), &factor.pow(exponent - 1 - k), prime);
+            let digit = baby_step_giant_step(&gamma, &h, prime, &factor)?;
+            x_i += digit * &factor_power;
+            factor_power *= &factor;
+        }
+        congruences.push((x_i, prime_power));
+    }
+
+    let x = number_theory::crt(&congruences)?;
+    (ZKP::exponentiate(g, &x, prime) == *y).then_some(x)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn toy_zkp() -> ZKP {
+        ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        }
+    }
+
+    #[test]
+    fn test_solvers_on_toy_example() {
+        let zkp = toy_zkp();
+        for secret in 0..11_u32 {
+            let y = ZKP::exponentiate(&zkp.alpha, &BigUint::from(secret), &zkp.prime);
+            let expected = Some(BigUint::from(secret));
+            assert_eq!(baby_step_giant_step(&zkp.alpha, &y, &zkp.prime, &zkp.order), expected);
+            assert_eq!(pohlig_hellman(&zkp.alpha, &y, &zkp.prime, &zkp.order), expected);
+        }
+
+        // y1 = 2 of the toy example
+        let x = pollard_rho(&zkp.alpha, &BigUint::from(2_u32), &zkp.prime, &zkp.order);
+        assert_eq!(x, Some(BigUint::from(6_u32)));
+        assert_eq!(baby_step_giant_step(&zkp.alpha, &BigUint::from(5_u32), &zkp.prime, &zkp.order), None);
+    }
+
+    #[test]
+    fn test_pohlig_hellman_over_full_group() {
+        // 5 generates Z_23^* of order 22 = 2 * 11
+        let prime = BigUint::from(23_u32);
+        let g = BigUint::from(5_u32);
+        let order = BigUint::from(22_u32);
+        for secret in 0..22_u32 {
+            let y = ZKP::exponentiate(&g, &BigUint::from(secret), &prime);
+            assert_eq!(pohlig_hellman(&g, &y, &prime, &order), Some(BigUint::from(secret)));
+        }
+    }
+
+    #[test]
+    fn test_solvers_on_32_bit_subgroup() {
+        let config = crate::paramgen::ParamGenConfig { p_bits: 64, q_bits: 32, rounds: 20, safe_prime: false };
+        let zkp = crate::paramgen::generate(&config);
+        let x = ZKP::generate_random_number(&zkp.order);
+        let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+
+        assert_eq!(baby_step_giant_step(&zkp.alpha, &y, &zkp.prime, &zkp.order), Some(x.clone()));
+        assert_eq!(pollard_rho(&zkp.alpha, &y, &zkp.prime, &zkp.order), Some(x));
+    }
+}
@@ -0,0 +1,170 @@
+use num_bigint::BigUint;
+
+use crate::{dleq::{self, DleqProof}, ZKP};
+
+/// (c1, c2) = (alpha^r, m * y^r)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ciphertext {
+    pub c1: BigUint,
+    pub c2: BigUint,
+}
+
+/// random scalar in [1, q), x = 0 or r = 0 would leave m or the key in the clear
+fn random_nonzero_scalar(zkp: &ZKP) -> BigUint {
+    ZKP::generate_random_number(&(&zkp.order - 1u32)) + 1u32
+}
+
+/// key pair (x, y = alpha^x) with x in [1, q)
+pub fn keygen(zkp: &ZKP) -> (BigUint, BigUint) {
+    let x = random_nonzero_scalar(zkp);
+    let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+    (x, y)
+}
+
+/// encrypts a group element m under the public key y, with r in [1, q)
+pub fn encrypt(zkp: &ZKP, y: &BigUint, m: &BigUint) -> Ciphertext {
+    let r = random_nonzero_scalar(zkp);
+    let c1 = ZKP::exponentiate(&zkp.alpha, &r, &zkp.prime);
+    let c2 = (m * ZKP::exponentiate(y, &r, &zkp.prime)) % &zkp.prime;
+    Ciphertext { c1, c2 }
+}
+
+/// d = c1^x, the decryption share that removes the mask y^r
+pub fn decryption_factor(zkp: &ZKP, x: &BigUint, ciphertext: &Ciphertext) -> BigUint {
+    ZKP::exponentiate(&ciphertext.c1, x, &zkp.prime)
+}
+
+/// m = c2 / d mod p
+/// returns None if d is not invertible, i.e. not a valid decryption factor
+pub fn unmask(zkp: &ZKP, ciphertext: &Ciphertext, d: &BigUint) -> Option<BigUint> {
+    let inverse = d.modinv(&zkp.prime)?;
+    Some((&ciphertext.c2 * inverse) % &zkp.prime)
+}
+
+/// m = c2 / c1^x mod p
+/// returns None if c1 is not a group element, e.g. c1 = 0 mod p in an untrusted ciphertext
+pub fn decrypt(zkp: &ZKP, x: &BigUint, ciphertext: &Ciphertext) -> Option<BigUint> {
+    if !zkp.is_group_element(&ciphertext.c1) {
+        return None;
+    }
+    unmask(zkp, ciphertext, &decryption_factor(zkp, x, ciphertext))
+}
+
+/// (c1 * alpha^r', c2 * y^r') decrypts to the same m but is unlinkable to the input
+pub fn rerandomize(zkp: &ZKP, y: &BigUint, ciphertext: &Ciphertext) -> Ciphertext {
+    let r = random_nonzero_scalar(zkp);
+    let c1 = (&ciphertext.c1 * ZKP::exponentiate(&zkp.alpha, &r, &zkp.prime)) % &zkp.prime;
+    let c2 = (&ciphertext.c2 * ZKP::exponentiate(y, &r, &zkp.prime)) % &zkp.prime;
+    Ciphertext { c1, c2 }
+}
+
+/// decrypts and proves log_alpha(y) == log_c1(d), so the plaintext is correct without revealing x
+/// returns (m, d, proof), or None if c1 is not a group element
+pub fn decrypt_with_proof(zkp: &ZKP, x: &BigUint, ciphertext: &Ciphertext) -> Option<(BigUint, BigUint, DleqProof)> {
+    if !zkp.is_group_element(&ciphertext.c1) {
+        return None;
+    }
+    let y = ZKP::exponentiate(&zkp.alpha, x, &zkp.prime);
+    let d = decryption_factor(zkp, x, ciphertext);
+    let group = dleq::with_second_base(zkp, ciphertext.c1.clone());
+    let proof = dleq::prove(&group, x, &y, &d);
+    let m = unmask(zkp, ciphertext, &d)?;
+    Some((m, d, proof))
+}
+
+/// checks a claimed plaintext m against the key holder's decryption factor and proof
+pub fn verify_decryption(
+    zkp: &ZKP,
+    y: &BigUint,
+    ciphertext: &Ciphertext,
+    m: &BigUint,
+    d: &BigUint,
+    proof: &DleqProof,
+) -> bool {
+    if !zkp.is_group_element(&ciphertext.c1) || !zkp.is_group_element(d) {
+        return false;
+    }
+    let group = dleq::with_second_base(zkp, ciphertext.c1.clone());
+    dleq::verify(&group, y, d, proof) && unmask(zkp, ciphertext, d).as_ref() == Some(m)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_elgamal_toy_example() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let (x, y) = keygen(&zkp);
+        let m = BigUint::from(13_u32);
+
+        let ciphertext = encrypt(&zkp, &y, &m);
+        assert_eq!(decrypt(&zkp, &x, &ciphertext), Some(m.clone()));
+        assert_eq!(decrypt(&zkp, &x, &rerandomize(&zkp, &y, &ciphertext)), Some(m.clone()));
+
+        // c1 = 0 or c1 = p - 1 (order 2) are not group elements and must not panic
+        for c1 in [BigUint::from(0_u32), BigUint::from(23_u32), BigUint::from(22_u32)] {
+            let forged = Ciphertext { c1, c2: m.clone() };
+            assert_eq!(decrypt(&zkp, &x, &forged), None);
+            assert!(decrypt_with_proof(&zkp, &x, &forged).is_none());
+        }
+    }
+
+    #[test]
+    fn test_keygen_never_returns_zero() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        for _ in 0..200 {
+            let (x, y) = keygen(&zkp);
+            assert!(x > BigUint::from(0_u32) && x < zkp.order);
+            assert_ne!(y, BigUint::from(1_u32));
+        }
+    }
+
+    #[test]
+    fn test_encrypt_never_sends_m_in_the_clear() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let (_, y) = keygen(&zkp);
+        let m = BigUint::from(13_u32);
+        // r = 0 would give (1, m), with 200 draws from [0, 11) it would show up
+        for _ in 0..200 {
+            assert_ne!(encrypt(&zkp, &y, &m).c1, BigUint::from(1_u32));
+            let unchanged = Ciphertext { c1: BigUint::from(4_u32), c2: m.clone() };
+            assert_ne!(rerandomize(&zkp, &y, &unchanged), unchanged);
+        }
+    }
+
+    #[test]
+    fn test_verifiable_decryption_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let (x, y) = keygen(&zkp);
+        let m = ZKP::exponentiate(&zkp.alpha, &BigUint::from(42_u32), &zkp.prime);
+
+        let ciphertext = rerandomize(&zkp, &y, &encrypt(&zkp, &y, &m));
+        assert_ne!(ciphertext.c2, m);
+
+        let (decrypted, d, proof) = decrypt_with_proof(&zkp, &x, &ciphertext).unwrap();
+        assert_eq!(decrypted, m);
+        assert!(verify_decryption(&zkp, &y, &ciphertext, &m, &d, &proof));
+
+        let wrong_m = (&m * &zkp.alpha) % &zkp.prime;
+        assert!(!verify_decryption(&zkp, &y, &ciphertext, &wrong_m, &d, &proof));
+        let wrong_d = (&d * &zkp.alpha) % &zkp.prime;
+        assert!(!verify_decryption(&zkp, &y, &ciphertext, &wrong_m, &wrong_d, &proof));
+    }
+}
@@ -0,0 +1,516 @@
+use std::fmt;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::{paramgen, params, ZKP};
+
+pub const VERSION: u8 = 1;
+const PARAMETERS_TAG: u8 = 0x01;
+const HEADER_SIZE: usize = 2;
+const GROUP_ID_SIZE: usize = 32;
+
+#[derive(Debug)]
+pub enum EncodingError {
+    UnsupportedVersion(u8),
+    WrongTag { expected: u8, found: u8 },
+    WrongLength { expected: usize, found: usize },
+    WrongGroup,
+    NonMinimal(&'static str),
+    Unreduced(&'static str),
+    NotInGroup(&'static str),
+    InvalidGroup(params::ParamError),
+    Malformed(String),
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::UnsupportedVersion(version) => write!(f, "unsupported encoding version {}", version),
+            EncodingError::WrongTag { expected, found } => write!(f, "expected tag {:#04x}, found {:#04x}", expected, found),
+            EncodingError::WrongLength { expected, found } => write!(f, "expected {} bytes, found {}", expected, found),
+            EncodingError::WrongGroup => write!(f, "value was encoded for a different group"),
+            EncodingError::NonMinimal(name) => write!(f, "{} has leading zeros", name),
+            EncodingError::Unreduced(name) => write!(f, "{} is not reduced", name),
+            EncodingError::NotInGroup(name) => write!(f, "{} is not in the order-q subgroup", name),
+            EncodingError::InvalidGroup(error) => write!(f, "{}", error),
+            EncodingError::Malformed(reason) => write!(f, "malformed encoding: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/// how a value is bounded, and so how wide its encoding is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    /// element of the order-q subgroup of Z_p^*, as many bytes as p
+    Element,
+    /// integer mod q, as many bytes as q
+    Scalar,
+}
+
+/// value with a fixed-width encoding relative to a group:
+/// version, tag, SHA-256 group id, then every field big-endian and zero-padded to its kind's width
+pub trait Canonical: Sized {
+    const TAG: u8;
+    /// name and kind of every field, in encoding order
+    const FIELDS: &'static [(&'static str, Kind)];
+
+    fn values(&self) -> Vec<&BigUint>;
+    fn from_values(values: Vec<BigUint>) -> Self;
+
+    /// every field is reduced, and elements lie in the subgroup
+    fn check(&self, zkp: &ZKP) -> Result<(), EncodingError> {
+        for (value, (name, kind)) in self.values().into_iter().zip(Self::FIELDS) {
+            check_value(zkp, value, name, *kind)?;
+        }
+        Ok(())
+    }
+}
+
+/// y1 = alpha^x, y2 = beta^x
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Statement {
+    #[cfg_attr(feature = "serde", serde(with = "serde_biguint"))]
+    pub y1: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "serde_biguint"))]
+    pub y2: BigUint,
+}
+
+/// r1 = alpha^k, r2 = beta^k
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Commitment {
+    #[cfg_attr(feature = "serde", serde(with = "serde_biguint"))]
+    pub r1: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "serde_biguint"))]
+    pub r2: BigUint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Challenge {
+    #[cfg_attr(feature = "serde", serde(with = "serde_biguint"))]
+    pub c: BigUint,
+}
+
+/// s = k - c * x mod q
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Response {
+    #[cfg_attr(feature = "serde", serde(with = "serde_biguint"))]
+    pub s: BigUint,
+}
+
+impl Canonical for Statement {
+    const TAG: u8 = 0x02;
+    const FIELDS: &'static [(&'static str, Kind)] = &[("y1", Kind::Element), ("y2", Kind::Element)];
+
+    fn values(&self) -> Vec<&BigUint> {
+        vec![&self.y1, &self.y2]
+    }
+
+    fn from_values(values: Vec<BigUint>) -> Self {
+        let [y1, y2]: [BigUint; 2] = values.try_into().expect("two fields");
+        Statement { y1, y2 }
+    }
+}
+
+impl Canonical for Commitment {
+    const TAG: u8 = 0x03;
+    const FIELDS: &'static [(&'static str, Kind)] = &[("r1", Kind::Element), ("r2", Kind::Element)];
+
+    fn values(&self) -> Vec<&BigUint> {
+        vec![&self.r1, &self.r2]
+    }
+
+    fn from_values(values: Vec<BigUint>) -> Self {
+        let [r1, r2]: [BigUint; 2] = values.try_into().expect("two fields");
+        Commitment { r1, r2 }
+    }
+}
+
+impl Canonical for Challenge {
+    const TAG: u8 = 0x04;
+    const FIELDS: &'static [(&'static str, Kind)] = &[("c", Kind::Scalar)];
+
+    fn values(&self) -> Vec<&BigUint> {
+        vec![&self.c]
+    }
+
+    fn from_values(values: Vec<BigUint>) -> Self {
+        let [c]: [BigUint; 1] = values.try_into().expect("one field");
+        Challenge { c }
+    }
+}
+
+impl Canonical for Response {
+    const TAG: u8 = 0x05;
+    const FIELDS: &'static [(&'static str, Kind)] = &[("s", Kind::Scalar)];
+
+    fn values(&self) -> Vec<&BigUint> {
+        vec![&self.s]
+    }
+
+    fn from_values(values: Vec<BigUint>) -> Self {
+        let [s]: [BigUint; 1] = values.try_into().expect("one field");
+        Response { s }
+    }
+}
+
+fn width(zkp: &ZKP, kind: Kind) -> usize {
+    match kind {
+        Kind::Element => zkp.prime.bits().div_ceil(8) as usize,
+        Kind::Scalar => zkp.order.bits().div_ceil(8) as usize,
+    }
+}
+
+fn check_value(zkp: &ZKP, value: &BigUint, name: &'static str, kind: Kind) -> Result<(), EncodingError> {
+    match kind {
+        Kind::Element if *value >= zkp.prime => Err(EncodingError::Unreduced(name)),
+        Kind::Element if !zkp.is_group_element(value) => Err(EncodingError::NotInGroup(name)),
+        Kind::Scalar if *value >= zkp.order => Err(EncodingError::Unreduced(name)),
+        _ => Ok(()),
+    }
+}
+
+/// big-endian, left-padded with zeros to `width` bytes
+fn write_fixed(value: &BigUint, width: usize, out: &mut Vec<u8>) {
+    let bytes = value.to_bytes_be();
+    assert!(bytes.len() <= width, "value does not fit its encoding width");
+    out.resize(out.len() + width - bytes.len(), 0);
+    out.extend_from_slice(&bytes);
+}
+
+/// big-endian with a u16 length prefix and no leading zeros
+fn write_minimal(value: &BigUint, out: &mut Vec<u8>) {
+    let bytes = value.to_bytes_be();
+    out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    out.extend_from_slice(&bytes);
+}
+
+fn read_minimal<'a>(input: &'a [u8], name: &'static str) -> Result<(BigUint, &'a [u8]), EncodingError> {
+    let too_short = || EncodingError::Malformed(format!("{} is truncated", name));
+    let (length, rest) = input.split_first_chunk::<2>().ok_or_else(too_short)?;
+    let length = u16::from_be_bytes(*length) as usize;
+    if rest.len() < length {
+        return Err(too_short());
+    }
+    let (bytes, rest) = rest.split_at(length);
+    if bytes.first() == Some(&0) {
+        return Err(EncodingError::NonMinimal(name));
+    }
+    Ok((BigUint::from_bytes_be(bytes), rest))
+}
+
+fn check_header(bytes: &[u8], tag: u8) -> Result<(), EncodingError> {
+    match bytes {
+        [VERSION, found, ..] if *found == tag => Ok(()),
+        [VERSION, found, ..] => Err(EncodingError::WrongTag { expected: tag, found: *found }),
+        [version, ..] if *version != VERSION => Err(EncodingError::UnsupportedVersion(*version)),
+        _ => Err(EncodingError::WrongLength { expected: HEADER_SIZE, found: bytes.len() }),
+    }
+}
+
+/// version, tag, p and q with minimal length-prefixed encodings, alpha and beta as wide as p
+pub fn encode_parameters(zkp: &ZKP) -> Vec<u8> {
+    let mut out = vec![VERSION, PARAMETERS_TAG];
+    write_minimal(&zkp.prime, &mut out);
+    write_minimal(&zkp.order, &mut out);
+    write_fixed(&zkp.alpha, width(zkp, Kind::Element), &mut out);
+    write_fixed(&zkp.beta, width(zkp, Kind::Element), &mut out);
+    out
+}
+
+/// inverse of `encode_parameters`; the decoded group is validated
+pub fn decode_parameters(bytes: &[u8]) -> Result<ZKP, EncodingError> {
+    check_header(bytes, PARAMETERS_TAG)?;
+    let (prime, rest) = read_minimal(&bytes[HEADER_SIZE..], "p")?;
+    let (order, rest) = read_minimal(rest, "q")?;
+    let element_width = prime.bits().div_ceil(8) as usize;
+    if rest.len() != 2 * element_width {
+        return Err(EncodingError::WrongLength { expected: bytes.len() - rest.len() + 2 * element_width, found: bytes.len() });
+    }
+    let (alpha, beta) = rest.split_at(element_width);
+    let zkp = ZKP {
+        prime,
+        order,
+        alpha: BigUint::from_bytes_be(alpha),
+        beta: BigUint::from_bytes_be(beta),
+    };
+    if zkp.alpha >= zkp.prime || zkp.beta >= zkp.prime {
+        return Err(EncodingError::Unreduced("generator"));
+    }
+    params::validate(&zkp, paramgen::DEFAULT_MILLER_RABIN_ROUNDS).map_err(EncodingError::InvalidGroup)?;
+    Ok(zkp)
+}
+
+/// SHA-256 of the canonical parameter encoding, binds every other encoding to its group
+pub fn group_id(zkp: &ZKP) -> [u8; GROUP_ID_SIZE] {
+    Sha256::digest(encode_parameters(zkp)).into()
+}
+
+pub fn encode<T: Canonical>(zkp: &ZKP, value: &T) -> Vec<u8> {
+    let mut out = vec![VERSION, T::TAG];
+    out.extend_from_slice(&group_id(zkp));
+    for (value, (_, kind)) in value.values().into_iter().zip(T::FIELDS) {
+        write_fixed(value, width(zkp, *kind), &mut out);
+    }
+    out
+}
+
+/// inverse of `encode`: exact length, matching group, every field reduced and elements in the subgroup
+pub fn decode<T: Canonical>(zkp: &ZKP, bytes: &[u8]) -> Result<T, EncodingError> {
+    check_header(bytes, T::TAG)?;
+    let expected = HEADER_SIZE + GROUP_ID_SIZE + T::FIELDS.iter().map(|(_, kind)| width(zkp, *kind)).sum::<usize>();
+    if bytes.len() != expected {
+        return Err(EncodingError::WrongLength { expected, found: bytes.len() });
+    }
+    let (id, mut rest) = bytes[HEADER_SIZE..].split_at(GROUP_ID_SIZE);
+    if id != group_id(zkp) {
+        return Err(EncodingError::WrongGroup);
+    }
+
+    let mut values = Vec::with_capacity(T::FIELDS.len());
+    for (_, kind) in T::FIELDS {
+        let (field, next) = rest.split_at(width(zkp, *kind));
+        values.push(BigUint::from_bytes_be(field));
+        rest = next;
+    }
+    let value = T::from_values(values);
+    value.check(zkp)?;
+    Ok(value)
+}
+
+/// integers as minimal big-endian bytes: a lowercase hex string for human-readable formats
+/// such as JSON, a byte string otherwise; leading zeros are rejected
+#[cfg(feature = "serde")]
+pub mod serde_biguint {
+    use num_bigint::BigUint;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &BigUint, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = value.to_bytes_be();
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(bytes))
+        } else {
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigUint, D::Error> {
+        let bytes = if deserializer.is_human_readable() {
+            let text = String::deserialize(deserializer)?;
+            if text.bytes().any(|byte| byte.is_ascii_uppercase()) {
+                return Err(D::Error::custom("hex must be lowercase"));
+            }
+            hex::decode(text).map_err(D::Error::custom)?
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)?
+        };
+        match bytes.first() {
+            None => Err(D::Error::custom("integer is empty")),
+            Some(0) if bytes.len() > 1 => Err(D::Error::custom("integer has leading zeros")),
+            _ => Ok(BigUint::from_bytes_be(&bytes)),
+        }
+    }
+
+    struct BytesVisitor;
+
+    impl serde::de::Visitor<'_> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a byte string")
+        }
+
+        fn visit_bytes<E: Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+            Ok(bytes.to_vec())
+        }
+
+        fn visit_byte_buf<E: Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+            Ok(bytes)
+        }
+    }
+}
+
+/// value tagged with the encoding version and its group, for the self-describing formats
+#[cfg(any(feature = "json", feature = "cbor"))]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Envelope<T> {
+    version: u8,
+    #[serde(with = "serde_biguint")]
+    group: BigUint,
+    value: T,
+}
+
+#[cfg(any(feature = "json", feature = "cbor"))]
+fn envelope<T>(zkp: &ZKP, value: T) -> Envelope<T> {
+    Envelope { version: VERSION, group: BigUint::from_bytes_be(&group_id(zkp)), value }
+}
+
+#[cfg(any(feature = "json", feature = "cbor"))]
+fn open_envelope<T: Canonical>(zkp: &ZKP, envelope: Envelope<T>) -> Result<T, EncodingError> {
+    if envelope.version != VERSION {
+        return Err(EncodingError::UnsupportedVersion(envelope.version));
+    }
+    if envelope.group != BigUint::from_bytes_be(&group_id(zkp)) {
+        return Err(EncodingError::WrongGroup);
+    }
+    envelope.value.check(zkp)?;
+    Ok(envelope.value)
+}
+
+#[cfg(feature = "json")]
+pub fn to_json<T: Canonical + serde::Serialize>(zkp: &ZKP, value: &T) -> String {
+    serde_json::to_string(&envelope(zkp, value)).expect("values always serialize")
+}
+
+#[cfg(feature = "json")]
+pub fn from_json<T: Canonical + serde::de::DeserializeOwned>(zkp: &ZKP, text: &str) -> Result<T, EncodingError> {
+    let envelope = serde_json::from_str(text).map_err(|error| EncodingError::Malformed(error.to_string()))?;
+    open_envelope(zkp, envelope)
+}
+
+/// parameters as JSON, validated on the way in
+#[cfg(feature = "json")]
+pub fn parameters_from_json(text: &str) -> Result<ZKP, EncodingError> {
+    let zkp: ZKP = serde_json::from_str(text).map_err(|error| EncodingError::Malformed(error.to_string()))?;
+    params::validate(&zkp, paramgen::DEFAULT_MILLER_RABIN_ROUNDS).map_err(EncodingError::InvalidGroup)?;
+    Ok(zkp)
+}
+
+#[cfg(feature = "cbor")]
+pub fn to_cbor<T: Canonical + serde::Serialize>(zkp: &ZKP, value: &T) -> Vec<u8> {
+    let mut out = Vec::new();
+    ciborium::into_writer(&envelope(zkp, value), &mut out).expect("values always serialize");
+    out
+}
+
+#[cfg(feature = "cbor")]
+pub fn from_cbor<T: Canonical + serde::de::DeserializeOwned>(zkp: &ZKP, bytes: &[u8]) -> Result<T, EncodingError> {
+    let envelope = ciborium::from_reader(bytes).map_err(|error| EncodingError::Malformed(error.to_string()))?;
+    open_envelope(zkp, envelope)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn toy_zkp() -> ZKP {
+        ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        }
+    }
+
+    #[test]
+    fn test_toy_example_encodings() {
+        let zkp = toy_zkp();
+        assert_eq!(encode_parameters(&zkp), [1, 1, 0, 1, 23, 0, 1, 11, 4, 9]);
+        assert_eq!(decode_parameters(&encode_parameters(&zkp)).unwrap(), zkp);
+
+        let statement = Statement { y1: BigUint::from(2_u32), y2: BigUint::from(3_u32) };
+        let bytes = encode(&zkp, &statement);
+        assert_eq!(bytes.len(), 2 + 32 + 2);
+        assert_eq!(bytes[34..], [2, 3]);
+        assert_eq!(decode::<Statement>(&zkp, &bytes).unwrap(), statement);
+
+        let response = Response { s: BigUint::from(5_u32) };
+        assert_eq!(decode::<Response>(&zkp, &encode(&zkp, &response)).unwrap(), response);
+    }
+
+    #[test]
+    fn test_fixed_width_with_1024_bit_constants() {
+        let zkp = ZKP::default();
+        let c = Challenge { c: BigUint::from(1_u32) };
+        let bytes = encode(&zkp, &c);
+        assert_eq!(bytes.len(), 2 + 32 + 20);
+        assert_eq!(decode::<Challenge>(&zkp, &bytes).unwrap(), c);
+
+        let commitment = Commitment { r1: zkp.alpha.clone(), r2: zkp.beta.clone() };
+        let bytes = encode(&zkp, &commitment);
+        assert_eq!(bytes.len(), 2 + 32 + 2 * 128);
+        assert_eq!(decode::<Commitment>(&zkp, &bytes).unwrap(), commitment);
+    }
+
+    #[test]
+    fn test_rejects_non_canonical_encodings() {
+        let zkp = toy_zkp();
+        let bytes = encode(&zkp, &Response { s: BigUint::from(5_u32) });
+
+        // s = 5 + q
+        let mut unreduced = bytes.clone();
+        unreduced[34] = 16;
+        assert!(matches!(decode::<Response>(&zkp, &unreduced), Err(EncodingError::Unreduced("s"))));
+
+        let mut padded = bytes.clone();
+        padded.insert(34, 0);
+        assert!(matches!(decode::<Response>(&zkp, &padded), Err(EncodingError::WrongLength { .. })));
+
+        assert!(matches!(decode::<Challenge>(&zkp, &bytes), Err(EncodingError::WrongTag { .. })));
+        let mut version = bytes.clone();
+        version[0] = 2;
+        assert!(matches!(decode::<Response>(&zkp, &version), Err(EncodingError::UnsupportedVersion(2))));
+        assert!(matches!(decode::<Response>(&ZKP::default(), &bytes), Err(EncodingError::WrongLength { .. })));
+
+        // 5 is below p but not in the order-11 subgroup
+        let mut statement = encode(&zkp, &Statement { y1: BigUint::from(2_u32), y2: BigUint::from(3_u32) });
+        statement[34] = 5;
+        assert!(matches!(decode::<Statement>(&zkp, &statement), Err(EncodingError::NotInGroup("y1"))));
+
+        // p = 0x0017 with a leading zero
+        let non_minimal = [1, 1, 0, 2, 0, 23, 0, 1, 11, 4, 9];
+        assert!(matches!(decode_parameters(&non_minimal), Err(EncodingError::NonMinimal("p"))));
+        let not_a_group = [1, 1, 0, 1, 23, 0, 1, 11, 4, 5];
+        assert!(matches!(decode_parameters(&not_a_group), Err(EncodingError::InvalidGroup(_))));
+    }
+
+    #[test]
+    fn test_group_id_binds_the_group() {
+        let zkp = toy_zkp();
+        let other = ZKP { beta: BigUint::from(3_u32), ..toy_zkp() };
+        let bytes = encode(&zkp, &Challenge { c: BigUint::from(3_u32) });
+        assert!(matches!(decode::<Challenge>(&other, &bytes), Err(EncodingError::WrongGroup)));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json() {
+        let zkp = toy_zkp();
+        let commitment = Commitment { r1: BigUint::from(8_u32), r2: BigUint::from(4_u32) };
+        let text = to_json(&zkp, &commitment);
+        assert!(text.contains(r#""value":{"r1":"08","r2":"04"}"#));
+        assert_eq!(from_json::<Commitment>(&zkp, &text).unwrap(), commitment);
+
+        let padded = text.replace(r#""r1":"08""#, r#""r1":"0008""#);
+        assert!(matches!(from_json::<Commitment>(&zkp, &padded), Err(EncodingError::Malformed(_))));
+        let unreduced = text.replace(r#""r1":"08""#, r#""r1":"1f""#);
+        assert!(matches!(from_json::<Commitment>(&zkp, &unreduced), Err(EncodingError::Unreduced("r1"))));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_parameters_json() {
+        let zkp = toy_zkp();
+        let text = serde_json::to_string(&zkp).unwrap();
+        assert_eq!(text, r#"{"prime":"17","order":"0b","alpha":"04","beta":"09"}"#);
+        assert_eq!(parameters_from_json(&text).unwrap(), zkp);
+        let wrong = text.replace(r#""beta":"09""#, r#""beta":"05""#);
+        assert!(matches!(parameters_from_json(&wrong), Err(EncodingError::InvalidGroup(_))));
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor() {
+        let zkp = ZKP::default();
+        let response = Response { s: &zkp.order - 1u32 };
+        let bytes = to_cbor(&zkp, &response);
+        assert_eq!(from_cbor::<Response>(&zkp, &bytes).unwrap(), response);
+        assert!(matches!(from_cbor::<Response>(&toy_zkp(), &bytes), Err(EncodingError::WrongGroup)));
+    }
+}
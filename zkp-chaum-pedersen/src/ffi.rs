@@ -0,0 +1,288 @@
+//! `extern "C"` API over the interactive protocol, declared in include/zkp_chaum_pedersen.h
+//!
+//! contexts, secrets and commitments are opaque handles created by `zkp_*_new` / `zkp_*_derive` /
+//! `zkp_commit` and released by the matching `zkp_*_free`. every function returns a `ZkpStatus`.
+//! numbers cross the boundary as big-endian bytes like the `zkp_auth.proto` fields: an output
+//! buffer is passed with its capacity in `*out_len`, which is overwritten with the length written,
+//! or with the length needed if the call fails with `ZKP_STATUS_BUFFER_TOO_SMALL`.
+//! `zkp_element_size` and `zkp_scalar_size` are always large enough.
+//!
+//! every pointer must be null or valid for the access the function makes,
+//! strings are NUL-terminated and handles are only freed once
+#![allow(clippy::missing_safety_doc)]
+
+use std::{
+    ffi::{c_char, CStr},
+    ptr, slice,
+};
+
+use num_bigint::BigUint;
+
+use crate::{dhparam, groups, params, ZKP};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZkpStatus {
+    Ok = 0,
+    /// the proof did not verify
+    Rejected = 1,
+    NullPointer = 2,
+    /// a string argument is not UTF-8
+    InvalidString = 3,
+    UnknownGroup = 4,
+    /// parameters that do not parse or do not form a valid group
+    InvalidParameters = 5,
+    /// a challenge that is not below q
+    InvalidChallenge = 6,
+    BufferTooSmall = 7,
+}
+
+/// group parameters (p, q, alpha, beta)
+pub struct ZkpContext {
+    zkp: ZKP,
+}
+
+/// the prover's x
+pub struct ZkpSecret {
+    x: BigUint,
+}
+
+/// the k behind (r1, r2) = (alpha^k, beta^k) of one login, it never leaves the handle
+pub struct ZkpCommitment {
+    k: BigUint,
+}
+
+unsafe fn boxed<T>(value: T, out: *mut *mut T) -> ZkpStatus {
+    if out.is_null() {
+        return ZkpStatus::NullPointer;
+    }
+    *out = Box::into_raw(Box::new(value));
+    ZkpStatus::Ok
+}
+
+unsafe fn free<T>(handle: *mut T) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+unsafe fn string<'a>(text: *const c_char) -> Result<&'a str, ZkpStatus> {
+    if text.is_null() {
+        return Err(ZkpStatus::NullPointer);
+    }
+    CStr::from_ptr(text).to_str().map_err(|_| ZkpStatus::InvalidString)
+}
+
+unsafe fn bytes<'a>(data: *const u8, len: usize) -> Result<&'a [u8], ZkpStatus> {
+    match (data.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(ZkpStatus::NullPointer),
+        (false, _) => Ok(slice::from_raw_parts(data, len)),
+    }
+}
+
+unsafe fn integer(data: *const u8, len: usize) -> Result<BigUint, ZkpStatus> {
+    Ok(BigUint::from_bytes_be(bytes(data, len)?))
+}
+
+unsafe fn write(value: &BigUint, out: *mut u8, out_len: *mut usize) -> ZkpStatus {
+    if out.is_null() || out_len.is_null() {
+        return ZkpStatus::NullPointer;
+    }
+    let value = value.to_bytes_be();
+    let capacity = *out_len;
+    *out_len = value.len();
+    if value.len() > capacity {
+        return ZkpStatus::BufferTooSmall;
+    }
+    ptr::copy_nonoverlapping(value.as_ptr(), out, value.len());
+    ZkpStatus::Ok
+}
+
+unsafe fn reference<'a, T>(handle: *const T) -> Result<&'a T, ZkpStatus> {
+    handle.as_ref().ok_or(ZkpStatus::NullPointer)
+}
+
+/// runs `body` and turns an early `Err` into its status
+fn status(body: impl FnOnce() -> Result<ZkpStatus, ZkpStatus>) -> ZkpStatus {
+    body().unwrap_or_else(|status| status)
+}
+
+/// the 1024-bit group of `ZKP::get_constants`
+#[no_mangle]
+pub unsafe extern "C" fn zkp_context_new_default(out: *mut *mut ZkpContext) -> ZkpStatus {
+    boxed(ZkpContext { zkp: ZKP::default() }, out)
+}
+
+/// one of the groups of `groups::GROUPS`, e.g. "toy-23"
+#[no_mangle]
+pub unsafe extern "C" fn zkp_context_new_group(name: *const c_char, out: *mut *mut ZkpContext) -> ZkpStatus {
+    status(|| {
+        let group = groups::by_name(string(name)?).ok_or(ZkpStatus::UnknownGroup)?;
+        let zkp = group.zkp().map_err(|_| ZkpStatus::InvalidParameters)?;
+        Ok(boxed(ZkpContext { zkp }, out))
+    })
+}
+
+/// parameters in the text format of `params::to_string` or as PKCS#3 / X9.42 PEM
+#[no_mangle]
+pub unsafe extern "C" fn zkp_context_new_params(text: *const c_char, out: *mut *mut ZkpContext) -> ZkpStatus {
+    status(|| {
+        let text = string(text)?;
+        let zkp = if text.trim_start().starts_with("-----BEGIN") {
+            dhparam::from_pem(text)
+        } else {
+            params::from_str(text)
+        };
+        let zkp = zkp.map_err(|_| ZkpStatus::InvalidParameters)?;
+        Ok(boxed(ZkpContext { zkp }, out))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn zkp_context_free(context: *mut ZkpContext) {
+    free(context)
+}
+
+/// bytes of p, the largest y1, y2, r1 or r2
+#[no_mangle]
+pub unsafe extern "C" fn zkp_element_size(context: *const ZkpContext) -> usize {
+    context.as_ref().map_or(0, |context| context.zkp.prime.bits().div_ceil(8) as usize)
+}
+
+/// bytes of q, the largest c or s
+#[no_mangle]
+pub unsafe extern "C" fn zkp_scalar_size(context: *const ZkpContext) -> usize {
+    context.as_ref().map_or(0, |context| context.zkp.order.bits().div_ceil(8) as usize)
+}
+
+/// x from the password bytes, as the command line client derives it
+#[no_mangle]
+pub unsafe extern "C" fn zkp_secret_derive(password: *const u8, password_len: usize, out: *mut *mut ZkpSecret) -> ZkpStatus {
+    status(|| Ok(boxed(ZkpSecret { x: integer(password, password_len)? }, out)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn zkp_secret_free(secret: *mut ZkpSecret) {
+    free(secret)
+}
+
+/// (y1, y2) = (alpha^x, beta^x) for `RegisterRequest`
+#[no_mangle]
+pub unsafe extern "C" fn zkp_register(
+    context: *const ZkpContext,
+    secret: *const ZkpSecret,
+    y1: *mut u8,
+    y1_len: *mut usize,
+    y2: *mut u8,
+    y2_len: *mut usize,
+) -> ZkpStatus {
+    status(|| {
+        let zkp = &reference(context)?.zkp;
+        let x = &reference(secret)?.x;
+        match write(&ZKP::exponentiate(&zkp.alpha, x, &zkp.prime), y1, y1_len) {
+            ZkpStatus::Ok => Ok(write(&ZKP::exponentiate(&zkp.beta, x, &zkp.prime), y2, y2_len)),
+            error => Err(error),
+        }
+    })
+}
+
+/// fresh k < q and (r1, r2) for `AuthenticationChallengeRequest`
+/// the commitment handle is only created if both values fit their buffers
+#[no_mangle]
+pub unsafe extern "C" fn zkp_commit(
+    context: *const ZkpContext,
+    out: *mut *mut ZkpCommitment,
+    r1: *mut u8,
+    r1_len: *mut usize,
+    r2: *mut u8,
+    r2_len: *mut usize,
+) -> ZkpStatus {
+    status(|| {
+        let zkp = &reference(context)?.zkp;
+        if out.is_null() {
+            return Err(ZkpStatus::NullPointer);
+        }
+        let (k, r1_value, r2_value) = zkp.commitment_with_rng(&mut rand::thread_rng());
+        for (value, buffer, len) in [(r1_value, r1, r1_len), (r2_value, r2, r2_len)] {
+            match write(&value, buffer, len) {
+                ZkpStatus::Ok => {}
+                error => return Err(error),
+            }
+        }
+        Ok(boxed(ZkpCommitment { k }, out))
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn zkp_commitment_free(commitment: *mut ZkpCommitment) {
+    free(commitment)
+}
+
+/// the verifier's random c < q
+#[no_mangle]
+pub unsafe extern "C" fn zkp_challenge(context: *const ZkpContext, c: *mut u8, c_len: *mut usize) -> ZkpStatus {
+    status(|| {
+        let zkp = &reference(context)?.zkp;
+        Ok(write(&ZKP::generate_random_number(&zkp.order), c, c_len))
+    })
+}
+
+/// s = k - c * x mod q for `AuthenticationAnswerRequest`
+#[no_mangle]
+pub unsafe extern "C" fn zkp_respond(
+    context: *const ZkpContext,
+    commitment: *const ZkpCommitment,
+    secret: *const ZkpSecret,
+    c: *const u8,
+    c_len: usize,
+    s: *mut u8,
+    s_len: *mut usize,
+) -> ZkpStatus {
+    status(|| {
+        let zkp = &reference(context)?.zkp;
+        let k = &reference(commitment)?.k;
+        let x = &reference(secret)?.x;
+        let c = integer(c, c_len)?;
+        if c >= zkp.order {
+            return Err(ZkpStatus::InvalidChallenge);
+        }
+        Ok(write(&zkp.solve(k, &c, x), s, s_len))
+    })
+}
+
+/// `ZKP_STATUS_OK` if r1 = alpha^s y1^c and r2 = beta^s y2^c, `ZKP_STATUS_REJECTED` otherwise
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn zkp_verify(
+    context: *const ZkpContext,
+    y1: *const u8,
+    y1_len: usize,
+    y2: *const u8,
+    y2_len: usize,
+    r1: *const u8,
+    r1_len: usize,
+    r2: *const u8,
+    r2_len: usize,
+    c: *const u8,
+    c_len: usize,
+    s: *const u8,
+    s_len: usize,
+) -> ZkpStatus {
+    status(|| {
+        let zkp = &reference(context)?.zkp;
+        let c = integer(c, c_len)?;
+        if c >= zkp.order {
+            return Err(ZkpStatus::InvalidChallenge);
+        }
+        let accepted = zkp.verify(
+            &integer(r1, r1_len)?,
+            &integer(r2, r2_len)?,
+            &integer(y1, y1_len)?,
+            &integer(y2, y2_len)?,
+            &c,
+            &integer(s, s_len)?,
+        );
+        Ok(if accepted { ZkpStatus::Ok } else { ZkpStatus::Rejected })
+    })
+}
@@ -0,0 +1,247 @@
+use num_bigint::BigUint;
+use rand::{Rng, RngCore};
+
+use crate::{hash, number_theory, paramgen, ZKP};
+
+const SECRET_DST: &[u8] = b"zkp-chaum-pedersen/ffs/secret";
+
+/// Feige-Fiat-Shamir identification over an RSA modulus n
+/// secrets s_1..s_k, public v_i = s_i^2 mod n
+/// per round: x = r^2 mod n, challenge bits a_1..a_k, y = r * prod s_i^a_i mod n,
+/// accept if y^2 = x * prod v_i^a_i mod n
+/// a cheating prover passes one round with probability 2^-k and all rounds with 2^-(k * rounds)
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeigeFiatShamir {
+    pub modulus: BigUint,
+    /// k, the number of secrets and challenge bits per round
+    pub parallel: usize,
+    pub rounds: usize,
+}
+
+impl FeigeFiatShamir {
+    /// fresh `modulus_bits`-bit n = p * q with two primes of half the bits each
+    pub fn generate(modulus_bits: u64, parallel: usize, rounds: usize) -> FeigeFiatShamir {
+        let prime_bits = modulus_bits / 2;
+        loop {
+            let p = paramgen::random_prime(prime_bits, paramgen::DEFAULT_MILLER_RABIN_ROUNDS);
+            let q = paramgen::random_prime(modulus_bits - prime_bits, paramgen::DEFAULT_MILLER_RABIN_ROUNDS);
+            let modulus = &p * &q;
+            if p != q && modulus.bits() == modulus_bits {
+                return FeigeFiatShamir { modulus, parallel, rounds };
+            }
+        }
+    }
+
+    /// 2^-(k * rounds)
+    pub fn soundness_error(&self) -> f64 {
+        0.5_f64.powi((self.parallel * self.rounds) as i32)
+    }
+
+    /// 0 < value < n and gcd(value, n) = 1
+    pub fn is_unit(&self, value: &BigUint) -> bool {
+        *value > BigUint::from(0u32) && *value < self.modulus && number_theory::mod_inverse(value, &self.modulus).is_some()
+    }
+
+    fn generate_random_unit(&self) -> BigUint {
+        loop {
+            let value = ZKP::generate_random_number(&self.modulus);
+            if self.is_unit(&value) {
+                return value;
+            }
+        }
+    }
+
+    /// a unit that is not a square root of 1 (v_i = 1 would prove nothing)
+    fn is_valid_secret(&self, s: &BigUint) -> bool {
+        self.is_unit(s) && (s * s) % &self.modulus != BigUint::from(1u32)
+    }
+
+    /// k random secrets
+    pub fn generate_secrets(&self) -> Vec<BigUint> {
+        (0..self.parallel)
+            .map(|_| loop {
+                let s = self.generate_random_unit();
+                if self.is_valid_secret(&s) {
+                    return s;
+                }
+            })
+            .collect()
+    }
+
+    /// s_i = H(password, i, attempt) mod n, so the prover only needs to remember the password
+    /// tries the next attempt if s_i is not a valid secret
+    pub fn secrets_from_password(&self, password: &[u8]) -> Vec<BigUint> {
+        let size = (self.modulus.bits() as usize + 128).div_ceil(8);
+        (0..self.parallel as u32)
+            .map(|i| {
+                (0u32..)
+                    .map(|attempt| {
+                        let parts: [&[u8]; 3] = [password, &i.to_be_bytes(), &attempt.to_be_bytes()];
+                        BigUint::from_bytes_be(&hash::expand(SECRET_DST, &parts, size)) % &self.modulus
+                    })
+                    .find(|s| self.is_valid_secret(s))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    /// v_i = s_i^2 mod n
+    pub fn public_keys(&self, secrets: &[BigUint]) -> Vec<BigUint> {
+        secrets.iter().map(|s| (s * s) % &self.modulus).collect()
+    }
+
+    /// (r, x = r^2 mod n)
+    pub fn commitment(&self) -> (BigUint, BigUint) {
+        let r = self.generate_random_unit();
+        let x = (&r * &r) % &self.modulus;
+        (r, x)
+    }
+
+    /// k random bits
+    pub fn generate_challenge(&self) -> Vec<bool> {
+        self.generate_challenge_with_rng(&mut rand::thread_rng())
+    }
+
+    pub fn generate_challenge_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> Vec<bool> {
+        (0..self.parallel).map(|_| rng.gen()).collect()
+    }
+
+    /// y = r * prod s_i^a_i mod n
+    pub fn solve(&self, r: &BigUint, secrets: &[BigUint], bits: &[bool]) -> BigUint {
+        secrets
+            .iter()
+            .zip(bits)
+            .filter(|(_, bit)| **bit)
+            .fold(r.clone(), |y, (s, _)| (y * s) % &self.modulus)
+    }
+
+    /// y^2 = x * prod v_i^a_i mod n
+    pub fn verify(&self, x: &BigUint, public: &[BigUint], bits: &[bool], y: &BigUint) -> bool {
+        if public.len() != self.parallel || bits.len() != self.parallel || !self.is_unit(x) || !self.is_unit(y) {
+            return false;
+        }
+        let rhs = public
+            .iter()
+            .zip(bits)
+            .filter(|(_, bit)| **bit)
+            .fold(x.clone(), |product, (v, _)| (product * v) % &self.modulus);
+        (y * y) % &self.modulus == rhs
+    }
+
+    /// prover without the secrets betting on the challenge bits `guess`:
+    /// x = y^2 / prod v_i^guess_i mod n passes exactly when the challenge equals the guess
+    pub fn forge_commitment(&self, public: &[BigUint], guess: &[bool]) -> (BigUint, BigUint) {
+        let y = self.generate_random_unit();
+        let denominator = public
+            .iter()
+            .zip(guess)
+            .filter(|(_, bit)| **bit)
+            .fold(BigUint::from(1u32), |product, (v, _)| (product * v) % &self.modulus);
+        let inverse = number_theory::mod_inverse(&denominator, &self.modulus).expect("public keys are units");
+        let x = (&y * &y * inverse) % &self.modulus;
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn toy_ffs(parallel: usize, rounds: usize) -> FeigeFiatShamir {
+        // n = 61 * 53
+        FeigeFiatShamir { modulus: BigUint::from(3233_u32), parallel, rounds }
+    }
+
+    fn soundness_ffs(parallel: usize, rounds: usize) -> FeigeFiatShamir {
+        // n = (2^61 - 1) * (2^89 - 1), large enough that products of the v_i do not collide by chance
+        let one = BigUint::from(1_u32);
+        let modulus = ((&one << 61) - &one) * ((&one << 89) - &one);
+        FeigeFiatShamir { modulus, parallel, rounds }
+    }
+
+    /// fraction of `trials` identifications a prover without the secrets passes
+    /// the guesses and challenges come from a seeded RNG, so the rate is the same on every run
+    fn cheating_success_rate(ffs: &FeigeFiatShamir, trials: usize) -> f64 {
+        let mut rng = StdRng::seed_from_u64(44);
+        let public = ffs.public_keys(&ffs.generate_secrets());
+        let successes = (0..trials)
+            .filter(|_| {
+                (0..ffs.rounds).all(|_| {
+                    let guess = ffs.generate_challenge_with_rng(&mut rng);
+                    let (x, y) = ffs.forge_commitment(&public, &guess);
+                    ffs.verify(&x, &public, &ffs.generate_challenge_with_rng(&mut rng), &y)
+                })
+            })
+            .count();
+        successes as f64 / trials as f64
+    }
+
+    #[test]
+    fn test_toy_example() {
+        let ffs = toy_ffs(3, 5);
+        let secrets = ffs.generate_secrets();
+        let public = ffs.public_keys(&secrets);
+
+        for _ in 0..ffs.rounds {
+            let (r, x) = ffs.commitment();
+            let bits = ffs.generate_challenge();
+            let y = ffs.solve(&r, &secrets, &bits);
+            assert!(ffs.verify(&x, &public, &bits, &y));
+        }
+
+        // answering different bits than asked fails
+        let (r, x) = ffs.commitment();
+        let y = ffs.solve(&r, &secrets, &[true, false, false]);
+        assert!(!ffs.verify(&x, &public, &[false, false, false], &y));
+        assert!(!ffs.verify(&x, &public, &[false, false], &y));
+    }
+
+    #[test]
+    fn test_soundness_error_decreases_with_rounds() {
+        const TRIALS: usize = 4000;
+        let mut previous = 1.0;
+        for rounds in 1..=4 {
+            let ffs = soundness_ffs(1, rounds);
+            let rate = cheating_success_rate(&ffs, TRIALS);
+            assert!((rate - ffs.soundness_error()).abs() < 0.04, "{} rounds: {} vs {}", rounds, rate, ffs.soundness_error());
+            assert!(rate < previous);
+            previous = rate;
+        }
+    }
+
+    #[test]
+    fn test_soundness_error_decreases_with_parallel_bits() {
+        const TRIALS: usize = 4000;
+        for parallel in 1..=3 {
+            let ffs = soundness_ffs(parallel, 1);
+            let rate = cheating_success_rate(&ffs, TRIALS);
+            assert!((rate - ffs.soundness_error()).abs() < 0.04);
+        }
+        assert_eq!(toy_ffs(4, 10).soundness_error(), 0.5_f64.powi(40));
+    }
+
+    #[test]
+    fn test_secrets_from_password() {
+        let ffs = FeigeFiatShamir::generate(512, 4, 8);
+        let secrets = ffs.secrets_from_password(b"password");
+        assert_eq!(secrets, ffs.secrets_from_password(b"password"));
+        let public = ffs.public_keys(&secrets);
+
+        let (r, x) = ffs.commitment();
+        let bits = vec![true; 4];
+        assert!(ffs.verify(&x, &public, &bits, &ffs.solve(&r, &secrets, &bits)));
+        assert!(secrets.iter().all(|s| ffs.is_valid_secret(s)));
+        let wrong = ffs.secrets_from_password(b"passw0rd");
+        assert!(!ffs.verify(&x, &public, &bits, &ffs.solve(&r, &wrong, &bits)));
+    }
+
+    #[test]
+    fn test_secrets_from_password_are_valid_on_toy_modulus() {
+        // about 1 in 28 residues mod 3233 is a non-unit or a square root of 1
+        let ffs = toy_ffs(128, 1);
+        let secrets = ffs.secrets_from_password(b"password");
+        assert_eq!(secrets.len(), 128);
+        assert!(secrets.iter().all(|s| ffs.is_unit(s) && (s * s) % &ffs.modulus != BigUint::from(1_u32)));
+    }
+}
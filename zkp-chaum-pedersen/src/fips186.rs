@@ -0,0 +1,248 @@
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::{number_theory, paramgen, params::ParamError, ZKP};
+
+/// output length of SHA-256 in bits
+const OUTLEN: u64 = 256;
+const GGEN: &[u8] = b"ggen";
+pub const ALPHA_INDEX: u8 = 1;
+pub const BETA_INDEX: u8 = 2;
+
+/// (L, N) pairs allowed by FIPS 186-4 section 4.2
+pub const APPROVED_SIZES: [(u64, u64); 4] = [(1024, 160), (2048, 224), (2048, 256), (3072, 256)];
+
+/// everything needed to re-derive a group: p and q come from the seed and counter (A.1.1.2),
+/// alpha and beta are the canonical generators for the two indices (A.2.3)
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiableParameters {
+    pub prime: BigUint,
+    pub order: BigUint,
+    pub domain_parameter_seed: Vec<u8>,
+    pub counter: u32,
+}
+
+fn hash(data: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&Sha256::digest(data))
+}
+
+/// (seed + value) mod 2^seedlen as a seedlen-bit string
+fn add_to_seed(seed: &[u8], value: u64) -> Vec<u8> {
+    let modulus = BigUint::from(1u32) << (seed.len() * 8);
+    let sum = (BigUint::from_bytes_be(seed) + value) % modulus;
+    let bytes = sum.to_bytes_be();
+    let mut padded = vec![0u8; seed.len() - bytes.len()];
+    padded.extend_from_slice(&bytes);
+    padded
+}
+
+/// A.1.1.2 steps 6 - 7: q = 2^(N-1) + U + 1 - (U mod 2) with U = Hash(seed) mod 2^(N-1)
+fn candidate_q(seed: &[u8], n: u64) -> BigUint {
+    let one = BigUint::from(1u32);
+    let u = hash(seed) % (&one << (n - 1));
+    let parity = &u % 2u32;
+    (&one << (n - 1)) + u + one - parity
+}
+
+/// A.1.1.2 step 11.1 - 11.5 for one offset
+fn candidate_p(seed: &[u8], q: &BigUint, l: u64, offset: u64) -> BigUint {
+    let one = BigUint::from(1u32);
+    let n = l.div_ceil(OUTLEN) - 1;
+    let b = l - 1 - n * OUTLEN;
+
+    let mut w = BigUint::from(0u32);
+    for j in 0..=n {
+        let mut v = hash(&add_to_seed(seed, offset + j));
+        if j == n {
+            v %= &one << b;
+        }
+        w += v << (j * OUTLEN);
+    }
+    let x = w + (&one << (l - 1));
+    let c = &x % (q << 1);
+    x - (c - &one)
+}
+
+/// A.1.1.2 steps 10 - 11 for a q that passed the primality test
+/// returns (p, counter) or None if no p was found in 4L tries
+fn search_p(seed: &[u8], q: &BigUint, l: u64, rounds: usize) -> Option<(BigUint, u32)> {
+    let n = l.div_ceil(OUTLEN) - 1;
+    let lower_bound = BigUint::from(1u32) << (l - 1);
+    let mut offset = 1;
+    for counter in 0..(4 * l) {
+        let p = candidate_p(seed, q, l, offset);
+        if p >= lower_bound && number_theory::is_probable_prime(&p, rounds) {
+            return Some((p, counter as u32));
+        }
+        offset += n + 1;
+    }
+    None
+}
+
+/// A.1.1.2: generates p (L bits) and q (N bits) from random domain parameter seeds of `seed_size` bytes
+pub fn generate(l: u64, n: u64, seed_size: usize, rounds: usize) -> Result<VerifiableParameters, ParamError> {
+    if !APPROVED_SIZES.contains(&(l, n)) {
+        return Err(ParamError::InvalidGroup("(L, N) is not an approved FIPS 186-4 size"));
+    }
+    if seed_size * 8 < n as usize {
+        return Err(ParamError::InvalidGroup("domain parameter seed is shorter than N"));
+    }
+    loop {
+        let seed: Vec<u8> = (0..seed_size).map(|_| rand::random::<u8>()).collect();
+        if let Some(parameters) = generate_from_seed(&seed, l, n, rounds) {
+            return Ok(parameters);
+        }
+    }
+}
+
+/// A.1.1.2 steps 6 - 11 for one given seed, None if the seed yields no q or no p
+pub fn generate_from_seed(seed: &[u8], l: u64, n: u64, rounds: usize) -> Option<VerifiableParameters> {
+    let q = candidate_q(seed, n);
+    if !number_theory::is_probable_prime(&q, rounds) {
+        return None;
+    }
+    let (p, counter) = search_p(seed, &q, l, rounds)?;
+    Some(VerifiableParameters {
+        prime: p,
+        order: q,
+        domain_parameter_seed: seed.to_vec(),
+        counter,
+    })
+}
+
+/// A.2.3: g = Hash(seed || "ggen" || index || count)^((p - 1) / q) mod p for the first count giving g >= 2
+pub fn canonical_generator(prime: &BigUint, order: &BigUint, seed: &[u8], index: u8) -> Option<BigUint> {
+    let two = BigUint::from(2u32);
+    let e = (prime - 1u32) / order;
+    for count in 1..=u16::MAX {
+        let mut u = seed.to_vec();
+        u.extend_from_slice(GGEN);
+        u.push(index);
+        u.extend_from_slice(&count.to_be_bytes());
+        let g = hash(&u).modpow(&e, prime);
+        if g >= two {
+            return Some(g);
+        }
+    }
+    None
+}
+
+impl VerifiableParameters {
+    /// alpha and beta are canonical generators for indices 1 and 2, so log_alpha(beta) is unknown
+    pub fn to_zkp(&self) -> Result<ZKP, ParamError> {
+        let generator = |index| {
+            canonical_generator(&self.prime, &self.order, &self.domain_parameter_seed, index)
+                .ok_or(ParamError::InvalidGroup("no canonical generator found"))
+        };
+        Ok(ZKP {
+            prime: self.prime.clone(),
+            order: self.order.clone(),
+            alpha: generator(ALPHA_INDEX)?,
+            beta: generator(BETA_INDEX)?,
+        })
+    }
+
+    /// A.1.1.3: re-derives q and p from the seed and checks them and the counter
+    pub fn verify(&self, rounds: usize) -> Result<(), ParamError> {
+        let l = self.prime.bits();
+        let n = self.order.bits();
+        if !APPROVED_SIZES.contains(&(l, n)) {
+            return Err(ParamError::InvalidGroup("(L, N) is not an approved FIPS 186-4 size"));
+        }
+        if self.counter as u64 > 4 * l - 1 {
+            return Err(ParamError::InvalidGroup("counter is larger than 4L - 1"));
+        }
+        let seed = &self.domain_parameter_seed;
+        if seed.len() * 8 < n as usize {
+            return Err(ParamError::InvalidGroup("domain parameter seed is shorter than N"));
+        }
+
+        let q = candidate_q(seed, n);
+        if q != self.order || !number_theory::is_probable_prime(&q, rounds) {
+            return Err(ParamError::InvalidGroup("q does not match the domain parameter seed"));
+        }
+        match search_p(seed, &q, l, rounds) {
+            Some((p, counter)) if p == self.prime && counter == self.counter => Ok(()),
+            _ => Err(ParamError::InvalidGroup("p does not match the domain parameter seed and counter")),
+        }
+    }
+
+    /// A.2.4: checks claimed generators against the canonical ones
+    pub fn verify_generators(&self, zkp: &ZKP) -> Result<(), ParamError> {
+        let canonical = self.to_zkp()?;
+        if canonical.alpha != zkp.alpha || canonical.beta != zkp.beta {
+            return Err(ParamError::InvalidGroup("generators are not canonical for the seed"));
+        }
+        if !zkp.is_group_element(&zkp.alpha) || !zkp.is_group_element(&zkp.beta) {
+            return Err(ParamError::InvalidGroup("generators do not have order q"));
+        }
+        Ok(())
+    }
+}
+
+impl ZKP {
+    /// group re-derived and verified from a published seed and counter
+    pub fn from_domain_parameter_seed(seed: &[u8], counter: u32, l: u64, n: u64) -> Result<ZKP, ParamError> {
+        if !APPROVED_SIZES.contains(&(l, n)) {
+            return Err(ParamError::InvalidGroup("(L, N) is not an approved FIPS 186-4 size"));
+        }
+        let q = candidate_q(seed, n);
+        let n_blocks = l.div_ceil(OUTLEN) - 1;
+        let p = candidate_p(seed, &q, l, 1 + counter as u64 * (n_blocks + 1));
+        let parameters = VerifiableParameters {
+            prime: p,
+            order: q,
+            domain_parameter_seed: seed.to_vec(),
+            counter,
+        };
+        parameters.verify(paramgen::DEFAULT_MILLER_RABIN_ROUNDS)?;
+        parameters.to_zkp()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SEED: &str = "195CF324CDF98E81920496B4647004708400F2B5EBB7A6D62CDC36BBCFAED599";
+    const COUNTER: u32 = 583;
+
+    #[test]
+    fn test_derive_and_verify_from_published_seed() {
+        let seed = hex::decode(SEED).unwrap();
+        let zkp = ZKP::from_domain_parameter_seed(&seed, COUNTER, 1024, 160).unwrap();
+
+        assert_eq!(zkp.prime.bits(), 1024);
+        assert_eq!(zkp.order.bits(), 160);
+        assert!(zkp.is_group_element(&zkp.alpha) && zkp.is_group_element(&zkp.beta));
+        assert_eq!(zkp.alpha, canonical_generator(&zkp.prime, &zkp.order, &seed, ALPHA_INDEX).unwrap());
+
+        let parameters = VerifiableParameters {
+            prime: zkp.prime.clone(),
+            order: zkp.order.clone(),
+            domain_parameter_seed: seed,
+            counter: COUNTER,
+        };
+        assert!(parameters.verify_generators(&zkp).is_ok());
+        let swapped = ZKP { alpha: zkp.beta.clone(), beta: zkp.alpha.clone(), ..zkp };
+        assert!(parameters.verify_generators(&swapped).is_err());
+    }
+
+    #[test]
+    fn test_rejects_tampered_seed_or_counter() {
+        let seed = hex::decode(SEED).unwrap();
+        assert!(ZKP::from_domain_parameter_seed(&seed, COUNTER + 1, 1024, 160).is_err());
+        assert!(ZKP::from_domain_parameter_seed(&seed, COUNTER, 2048, 224).is_err());
+        assert!(ZKP::from_domain_parameter_seed(&seed, COUNTER, 1024, 100).is_err());
+
+        let mut tampered = seed.clone();
+        tampered[0] ^= 1;
+        assert!(ZKP::from_domain_parameter_seed(&tampered, COUNTER, 1024, 160).is_err());
+    }
+
+    #[test]
+    fn test_add_to_seed_wraps_around() {
+        assert_eq!(add_to_seed(&[0x00, 0xFF], 1), vec![0x01, 0x00]);
+        assert_eq!(add_to_seed(&[0xFF, 0xFF], 2), vec![0x00, 0x01]);
+    }
+}
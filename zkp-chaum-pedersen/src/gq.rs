@@ -0,0 +1,165 @@
+use num_bigint::BigUint;
+
+use crate::{number_theory, paramgen, ZKP};
+
+pub const DEFAULT_EXPONENT: u32 = 65537;
+
+/// smallest e the server accepts, a cheating prover passes one round with probability 1/e
+pub const MIN_EXPONENT: u32 = DEFAULT_EXPONENT;
+
+/// Guillou-Quisquater identification over an RSA modulus n with prime public exponent e
+/// secret x, public y = x^e mod n
+/// t = r^e mod n, c < e, s = r * x^c mod n, accept if s^e = t * y^c mod n
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuillouQuisquater {
+    pub modulus: BigUint,
+    pub exponent: BigUint,
+}
+
+impl GuillouQuisquater {
+    /// fresh `modulus_bits`-bit n = p * q with two primes of half the bits each, and e = 65537
+    pub fn generate(modulus_bits: u64) -> GuillouQuisquater {
+        let exponent = BigUint::from(DEFAULT_EXPONENT);
+        let prime_bits = modulus_bits / 2;
+        loop {
+            let p = paramgen::random_prime(prime_bits, paramgen::DEFAULT_MILLER_RABIN_ROUNDS);
+            let q = paramgen::random_prime(modulus_bits - prime_bits, paramgen::DEFAULT_MILLER_RABIN_ROUNDS);
+            let phi = (&p - 1u32) * (&q - 1u32);
+            let modulus = &p * &q;
+            if p != q && modulus.bits() == modulus_bits && number_theory::mod_inverse(&exponent, &phi).is_some() {
+                return GuillouQuisquater { modulus, exponent };
+            }
+        }
+    }
+
+    /// e is prime and smaller than an odd n that is neither prime nor a perfect power;
+    /// the challenge space is [0, e)
+    pub fn is_valid(&self) -> bool {
+        let two = BigUint::from(2u32);
+        self.modulus.bit(0)
+            && self.exponent > two
+            && self.exponent < self.modulus
+            && number_theory::is_probable_prime(&self.exponent, paramgen::DEFAULT_MILLER_RABIN_ROUNDS)
+            && self.is_hard_modulus()
+    }
+
+    /// anyone can take e-th roots mod a prime n, and mod p^k once p = n^(1/k) is known
+    fn is_hard_modulus(&self) -> bool {
+        !number_theory::is_probable_prime(&self.modulus, paramgen::DEFAULT_MILLER_RABIN_ROUNDS)
+            && (2..self.modulus.bits() as u32).all(|k| self.modulus.nth_root(k).pow(k) != self.modulus)
+    }
+
+    /// 0 < value < n and gcd(value, n) = 1
+    pub fn is_unit(&self, value: &BigUint) -> bool {
+        *value > BigUint::from(0u32) && *value < self.modulus && number_theory::mod_inverse(value, &self.modulus).is_some()
+    }
+
+    /// random unit mod n
+    pub fn generate_random_unit(&self) -> BigUint {
+        loop {
+            let value = ZKP::generate_random_number(&self.modulus);
+            if self.is_unit(&value) {
+                return value;
+            }
+        }
+    }
+
+    /// y = x^e mod n
+    pub fn public_key(&self, x: &BigUint) -> BigUint {
+        ZKP::exponentiate(x, &self.exponent, &self.modulus)
+    }
+
+    /// (r, t = r^e mod n)
+    pub fn commitment(&self) -> (BigUint, BigUint) {
+        let r = self.generate_random_unit();
+        let t = ZKP::exponentiate(&r, &self.exponent, &self.modulus);
+        (r, t)
+    }
+
+    /// c < e
+    pub fn generate_challenge(&self) -> BigUint {
+        ZKP::generate_random_number(&self.exponent)
+    }
+
+    /// s = r * x^c mod n
+    pub fn solve(&self, r: &BigUint, c: &BigUint, x: &BigUint) -> BigUint {
+        (r * ZKP::exponentiate(x, c, &self.modulus)) % &self.modulus
+    }
+
+    /// s^e = t * y^c mod n
+    pub fn verify(&self, t: &BigUint, y: &BigUint, c: &BigUint, s: &BigUint) -> bool {
+        if *c >= self.exponent || !self.is_unit(t) || !self.is_unit(y) || !self.is_unit(s) {
+            return false;
+        }
+        let lhs = ZKP::exponentiate(s, &self.exponent, &self.modulus);
+        let rhs = (t * ZKP::exponentiate(y, c, &self.modulus)) % &self.modulus;
+        lhs == rhs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn toy_gq() -> GuillouQuisquater {
+        // n = 61 * 53, gcd(17, 60 * 52) = 1
+        GuillouQuisquater {
+            modulus: BigUint::from(3233_u32),
+            exponent: BigUint::from(17_u32),
+        }
+    }
+
+    #[test]
+    fn test_toy_example() {
+        let gq = toy_gq();
+        assert!(gq.is_valid());
+
+        let x = BigUint::from(42_u32);
+        let y = gq.public_key(&x);
+        for c in 0..17_u32 {
+            let c = BigUint::from(c);
+            let (r, t) = gq.commitment();
+            let s = gq.solve(&r, &c, &x);
+            assert!(gq.verify(&t, &y, &c, &s));
+        }
+
+        // a wrong secret only passes the trivial challenge
+        let (r, t) = gq.commitment();
+        let x_fake = BigUint::from(43_u32);
+        assert!(gq.verify(&t, &y, &BigUint::from(0u32), &gq.solve(&r, &BigUint::from(0u32), &x_fake)));
+        assert!(!gq.verify(&t, &y, &BigUint::from(1u32), &gq.solve(&r, &BigUint::from(1u32), &x_fake)));
+
+        // the challenge must stay below e
+        let (r, t) = gq.commitment();
+        let c = BigUint::from(17_u32);
+        assert!(!gq.verify(&t, &y, &c, &gq.solve(&r, &c, &x)));
+    }
+
+    #[test]
+    fn test_generated_modulus() {
+        let gq = GuillouQuisquater::generate(512);
+        assert!(gq.is_valid());
+        assert_eq!(gq.modulus.bits(), 512);
+
+        let x = gq.generate_random_unit();
+        let y = gq.public_key(&x);
+        let (r, t) = gq.commitment();
+        let c = gq.generate_challenge();
+        let s = gq.solve(&r, &c, &x);
+        assert!(gq.verify(&t, &y, &c, &s));
+        assert!(!gq.verify(&t, &y, &c, &gq.solve(&r, &c, &gq.generate_random_unit())));
+    }
+
+    #[test]
+    fn test_rejects_invalid_parameters() {
+        let even = GuillouQuisquater { modulus: BigUint::from(3234_u32), ..toy_gq() };
+        assert!(!even.is_valid());
+        let composite = GuillouQuisquater { exponent: BigUint::from(15_u32), ..toy_gq() };
+        assert!(!composite.is_valid());
+        let prime = GuillouQuisquater { modulus: BigUint::from(3229_u32), ..toy_gq() };
+        assert!(!prime.is_valid());
+        let square = GuillouQuisquater { modulus: BigUint::from(61_u32 * 61), ..toy_gq() };
+        assert!(!square.is_valid());
+        assert!(!toy_gq().is_unit(&BigUint::from(61_u32)));
+    }
+}
@@ -0,0 +1,84 @@
+use num_bigint::BigUint;
+
+use crate::{paramgen, params::{self, ParamError}, ZKP};
+
+/// where a named group comes from
+pub enum Provenance {
+    /// fixed constants, checked with `params::validate`
+    Constants(fn() -> ZKP),
+    /// re-derived from a published FIPS 186-4 domain parameter seed on every load
+    DomainParameterSeed { seed: &'static str, counter: u32, l: u64, n: u64 },
+}
+
+pub struct NamedGroup {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub provenance: Provenance,
+}
+
+fn toy_group() -> ZKP {
+    ZKP {
+        prime: BigUint::from(23_u32),
+        order: BigUint::from(11_u32),
+        alpha: BigUint::from(4_u32),
+        beta: BigUint::from(9_u32),
+    }
+}
+
+pub const GROUPS: [NamedGroup; 3] = [
+    NamedGroup {
+        name: "rfc5114-1024-160",
+        description: "1024-bit group of RFC 5114 section 2.1, the default of get_constants",
+        provenance: Provenance::Constants(ZKP::default),
+    },
+    NamedGroup {
+        name: "fips186-1024-160",
+        description: "1024-bit group derived from seed = SHA-256(\"zkp-chaum-pedersen fips186-4 1024-160 #33\")",
+        provenance: Provenance::DomainParameterSeed {
+            seed: "195CF324CDF98E81920496B4647004708400F2B5EBB7A6D62CDC36BBCFAED599",
+            counter: 583,
+            l: 1024,
+            n: 160,
+        },
+    },
+    NamedGroup {
+        name: "toy-23",
+        description: "p = 23, q = 11 toy group of the course, insecure",
+        provenance: Provenance::Constants(toy_group),
+    },
+];
+
+impl NamedGroup {
+    /// builds the group, re-deriving and verifying seeded groups
+    pub fn zkp(&self) -> Result<ZKP, ParamError> {
+        match &self.provenance {
+            Provenance::Constants(constants) => Ok(constants()),
+            Provenance::DomainParameterSeed { seed, counter, l, n } => {
+                let seed = hex::decode(seed).map_err(|_| ParamError::InvalidHex("seed"))?;
+                ZKP::from_domain_parameter_seed(&seed, *counter, *l, *n)
+            }
+        }
+    }
+
+    pub fn verify(&self) -> Result<(), ParamError> {
+        params::validate(&self.zkp()?, paramgen::DEFAULT_MILLER_RABIN_ROUNDS)
+    }
+}
+
+pub fn by_name(name: &str) -> Option<&'static NamedGroup> {
+    GROUPS.iter().find(|group| group.name == name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry_groups_are_valid() {
+        for group in &GROUPS {
+            assert!(group.verify().is_ok(), "{} is invalid", group.name);
+        }
+        assert_eq!(by_name("rfc5114-1024-160").unwrap().zkp().unwrap(), ZKP::default());
+        assert!(by_name("unknown").is_none());
+    }
+}
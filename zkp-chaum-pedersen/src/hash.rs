@@ -0,0 +1,93 @@
+use alloc::vec::Vec;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::ZKP;
+
+/// SHA-256 over the domain separation tag, a block counter and the length-prefixed parts
+fn digest(dst: &[u8], counter: u32, parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update((dst.len() as u64).to_be_bytes());
+    hasher.update(dst);
+    hasher.update(counter.to_be_bytes());
+    for part in parts {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// expands the hash of the parts to `size` bytes
+pub fn expand(dst: &[u8], parts: &[&[u8]], size: usize) -> Vec<u8> {
+    let mut output = Vec::with_capacity(size + 32);
+    let mut counter = 0u32;
+    while output.len() < size {
+        output.extend_from_slice(&digest(dst, counter, parts));
+        counter += 1;
+    }
+    output.truncate(size);
+    output
+}
+
+/// H(parts) mod q
+/// hashes 128 bits more than q has so that the reduction bias is negligible
+pub fn hash_to_scalar(zkp: &ZKP, dst: &[u8], parts: &[&[u8]]) -> BigUint {
+    let size = (zkp.order.bits() as usize + 128).div_ceil(8);
+    BigUint::from_bytes_be(&expand(dst, parts, size)) % &zkp.order
+}
+
+/// H(parts)^((p - 1) / q) mod p, an element of the order-q subgroup with unknown discrete log
+/// tries the next counter value if the hash lands on the identity
+pub fn hash_to_group(zkp: &ZKP, dst: &[u8], parts: &[&[u8]]) -> BigUint {
+    let one = BigUint::from(1u32);
+    let cofactor = (&zkp.prime - &one) / &zkp.order;
+    let size = (zkp.prime.bits() as usize + 128).div_ceil(8);
+
+    let mut attempt = 0u32;
+    loop {
+        let attempt_bytes = attempt.to_be_bytes();
+        let mut tagged_parts = parts.to_vec();
+        tagged_parts.push(&attempt_bytes);
+
+        let candidate = BigUint::from_bytes_be(&expand(dst, &tagged_parts, size)) % &zkp.prime;
+        let element = ZKP::exponentiate(&candidate, &cofactor, &zkp.prime);
+        if element > one {
+            return element;
+        }
+        attempt += 1;
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hash_to_group_lands_in_subgroup() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+
+        for message in [b"a".as_slice(), b"b", b"hello", b""] {
+            let element = hash_to_group(&zkp, b"test", &[message]);
+            assert!(zkp.is_group_element(&element));
+            assert_ne!(element, BigUint::from(1_u32));
+            assert_eq!(element, hash_to_group(&zkp, b"test", &[message]));
+        }
+    }
+
+    #[test]
+    fn test_hash_is_domain_separated() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+
+        assert_ne!(hash_to_scalar(&zkp, b"one", &[b"m"]), hash_to_scalar(&zkp, b"two", &[b"m"]));
+        assert_ne!(hash_to_scalar(&zkp, b"one", &[b"ab", b"c"]), hash_to_scalar(&zkp, b"one", &[b"a", b"bc"]));
+        assert!(hash_to_scalar(&zkp, b"one", &[b"m"]) < zkp.order);
+        assert!(zkp.is_group_element(&hash_to_group(&zkp, b"one", &[b"m"])));
+    }
+}
@@ -1,10 +1,68 @@
-use num_bigint::{BigUint, RandBigInt};
-use rand::{self, Rng};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+use num_bigint::{BigUint, RandBigInt};
+use rand::RngCore;
+
+use nonce_reuse::Transcript;
+
+#[cfg(feature = "std")]
+pub mod challenge_rng;
+pub mod committed_challenge;
+#[cfg(feature = "std")]
+pub mod dhparam;
+#[cfg(feature = "std")]
+pub mod dkg;
+pub mod dleq;
+#[cfg(feature = "std")]
+pub mod dlog;
+#[cfg(feature = "std")]
+pub mod elgamal;
+#[cfg(feature = "std")]
+pub mod encoding;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod ffs;
+#[cfg(feature = "std")]
+pub mod fips186;
+#[cfg(feature = "std")]
+pub mod groups;
+#[cfg(feature = "std")]
+pub mod gq;
+pub mod hash;
+pub mod nonce_reuse;
+pub mod number_theory;
+#[cfg(feature = "std")]
+pub mod paramgen;
+#[cfg(feature = "std")]
+pub mod params;
+pub mod pedersen;
+pub mod schnorr;
+#[cfg(feature = "std")]
+pub mod shamir;
+#[cfg(feature = "std")]
+pub mod threshold;
+#[cfg(feature = "std")]
+pub mod voprf;
+#[cfg(feature = "std")]
+pub mod vss;
+#[cfg(feature = "std")]
+pub mod vrf;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZKP {
+    #[cfg_attr(feature = "serde", serde(with = "encoding::serde_biguint"))]
     pub prime: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "encoding::serde_biguint"))]
     pub order: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "encoding::serde_biguint"))]
     pub alpha: BigUint,
+    #[cfg_attr(feature = "serde", serde(with = "encoding::serde_biguint"))]
     pub beta: BigUint,
 }
 
@@ -14,16 +72,17 @@ impl ZKP {
         number.modpow(exponent, modulo)
     }
 
-    /// s = k - c * x mod q
+    /// 0 < y < p and y^q = 1 mod p, i.e. y lies in the subgroup generated by alpha
+    pub fn is_group_element(&self, y: &BigUint) -> bool {
+        let zero = BigUint::from(0u32);
+        let one = BigUint::from(1u32);
+        *y > zero && *y < self.prime && y.modpow(&self.order, &self.prime) == one
+    }
+
+    /// s = k - c * x mod q, always in [0, q)
     pub fn solve(&self, k: &BigUint, c: &BigUint, x: &BigUint) -> BigUint {
-        let exponent1 = &BigUint::from(1u32);
-        let cx = c * x;
-        if *k >= cx {
-            (k - cx).modpow(exponent1, &self.order)
-        }
-        else {
-            &self.order - (cx - k).modpow(exponent1, &self.order)
-        }
+        let cx = (c * x) % &self.order;
+        (k % &self.order + &self.order - cx) % &self.order
     }
 
     /// r1 = a^s y1^c
@@ -46,13 +105,69 @@ impl ZKP {
         cond1 && cond2
     }
 
-    pub fn generate_random_number(bound: &BigUint) -> BigUint {
-        let mut rng = rand::thread_rng();
+    /// min(2^bits, q), the number of possible challenges when c has `bits` bits
+    pub fn challenge_bound(&self, bits: u64) -> BigUint {
+        (BigUint::from(1u32) << bits).min(self.order.clone())
+    }
+
+    /// -log2 of the soundness error: a prover without x passes `repetitions`
+    /// independent challenges below `bound` with probability bound^-repetitions
+    #[cfg(feature = "std")]
+    pub fn soundness_bits(bound: &BigUint, repetitions: usize) -> f64 {
+        // the top 53 bits convert to f64 exactly, the rest only shift the logarithm
+        let shift = bound.bits().saturating_sub(53);
+        let top = (bound >> shift).iter_u64_digits().next().unwrap_or(0) as f64;
+        (top.log2() + shift as f64) * repetitions as f64
+    }
+
+    /// accepts if there is at least one transcript and every transcript
+    /// has a challenge below `bound` and verifies against (y1, y2)
+    pub fn verify_repeated(&self, y_1: &BigUint, y_2: &BigUint, transcripts: &[Transcript], bound: &BigUint) -> bool {
+        !transcripts.is_empty()
+            && transcripts
+                .iter()
+                .all(|t| t.c < *bound && self.verify(&t.r1, &t.r2, y_1, y_2, &t.c, &t.s))
+    }
+
+    /// x = (s1 - s2) / (c2 - c1) mod q
+    /// recovers the secret from two accepting transcripts that share (r1, r2)
+    /// returns None if both challenges are equal mod q
+    pub fn extract_secret(
+        &self,
+        c_1: &BigUint,
+        s_1: &BigUint,
+        c_2: &BigUint,
+        s_2: &BigUint,
+    ) -> Option<BigUint> {
+        let q = &self.order;
+        let numerator = (s_1 % q + q - s_2 % q) % q;
+        let denominator = (c_2 % q + q - c_1 % q) % q;
+        let inverse = denominator.modinv(q)?;
+        Some((numerator * inverse) % q)
+    }
+
+    /// k < q and the commitment (r1, r2) = (a^k, b^k)
+    pub fn commitment_with_rng<R: RngCore + ?Sized>(&self, rng: &mut R) -> (BigUint, BigUint, BigUint) {
+        let k = Self::generate_random_number_with_rng(&self.order, rng);
+        let r_1 = Self::exponentiate(&self.alpha, &k, &self.prime);
+        let r_2 = Self::exponentiate(&self.beta, &k, &self.prime);
+        (k, r_1, r_2)
+    }
 
+    /// uniform below `bound` from the caller's RNG, for targets without `thread_rng`
+    pub fn generate_random_number_with_rng<R: RngCore + ?Sized>(bound: &BigUint, rng: &mut R) -> BigUint {
         rng.gen_biguint_below(bound)
     }
 
+    #[cfg(feature = "std")]
+    pub fn generate_random_number(bound: &BigUint) -> BigUint {
+        Self::generate_random_number_with_rng(bound, &mut rand::thread_rng())
+    }
+
+    #[cfg(feature = "std")]
     pub fn generate_random_string(size: usize) -> String {
+        use rand::Rng;
+
         rand::thread_rng()
             .sample_iter(rand::distributions::Alphanumeric)
             .take(size)
@@ -83,10 +198,18 @@ impl ZKP {
     }
 }
 
-#[cfg(test)]
+impl Default for ZKP {
+    /// the 1024-bit group of `get_constants`
+    fn default() -> Self {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        ZKP { prime, order, alpha, beta }
+    }
+}
+
+// the tests of the no_std modules draw from thread_rng, so they only build with std
+#[cfg(all(test, feature = "std"))]
 mod test {
     use super::*;
-    use hex;
 
     #[test]
     fn test_toy_example() {
@@ -189,4 +312,215 @@ mod test {
         let cond = zkp.verify(&r_1, &r_2, &y_1, &y_2, &c, &s);
         assert!(cond)
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_one_bit_challenges_with_parallel_repetitions() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let x = BigUint::from(6_u32);
+        let y1 = BigUint::from(2_u32);
+        let y2 = BigUint::from(3_u32);
+
+        let bound = zkp.challenge_bound(1);
+        assert_eq!(bound, BigUint::from(2_u32));
+        assert_eq!(zkp.challenge_bound(8), zkp.order);
+        assert_eq!(ZKP::soundness_bits(&bound, 40), 40.0);
+
+        let transcripts: Vec<Transcript> = (0..40)
+            .map(|_| {
+                let k = ZKP::generate_random_number(&zkp.order);
+                let c = ZKP::generate_random_number(&bound);
+                Transcript {
+                    r1: ZKP::exponentiate(&zkp.alpha, &k, &zkp.prime),
+                    r2: ZKP::exponentiate(&zkp.beta, &k, &zkp.prime),
+                    s: zkp.solve(&k, &c, &x),
+                    c,
+                }
+            })
+            .collect();
+        assert!(zkp.verify_repeated(&y1, &y2, &transcripts, &bound));
+        assert!(!zkp.verify_repeated(&y1, &y2, &[], &bound));
+
+        // one bad answer fails the whole set
+        let mut tampered = transcripts.clone();
+        tampered[17].s = (&tampered[17].s + 1u32) % &zkp.order;
+        assert!(!zkp.verify_repeated(&y1, &y2, &tampered, &bound));
+
+        // a challenge outside the configured space is rejected even if it verifies
+        let k = BigUint::from(7_u32);
+        let c = BigUint::from(5_u32);
+        let outside = Transcript {
+            r1: ZKP::exponentiate(&zkp.alpha, &k, &zkp.prime),
+            r2: ZKP::exponentiate(&zkp.beta, &k, &zkp.prime),
+            s: zkp.solve(&k, &c, &x),
+            c,
+        };
+        assert!(!zkp.verify_repeated(&y1, &y2, &[outside], &bound));
+    }
+
+    #[test]
+    fn test_soundness_bits_with_1024_bit_constants() {
+        let zkp = ZKP::default();
+        let bits = ZKP::soundness_bits(&zkp.order, 1);
+        assert!(bits > zkp.order.bits() as f64 - 1.0 && bits <= zkp.order.bits() as f64);
+        assert_eq!(ZKP::soundness_bits(&zkp.challenge_bound(16), 3), 48.0);
+    }
+
+    #[test]
+    fn test_extract_secret_toy_example() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+
+        let secret = BigUint::from(6_u32);
+        let random_k = BigUint::from(7_u32);
+        let c_1 = BigUint::from(4_u32);
+        let c_2 = BigUint::from(9_u32);
+
+        let y_1 = ZKP::exponentiate(&zkp.alpha, &secret, &zkp.prime);
+        let y_2 = ZKP::exponentiate(&zkp.beta, &secret, &zkp.prime);
+        let r_1 = ZKP::exponentiate(&zkp.alpha, &random_k, &zkp.prime);
+        let r_2 = ZKP::exponentiate(&zkp.beta, &random_k, &zkp.prime);
+
+        let s_1 = zkp.solve(&random_k, &c_1, &secret);
+        let s_2 = zkp.solve(&random_k, &c_2, &secret);
+        assert!(zkp.verify(&r_1, &r_2, &y_1, &y_2, &c_1, &s_1));
+        assert!(zkp.verify(&r_1, &r_2, &y_1, &y_2, &c_2, &s_2));
+
+        assert_eq!(zkp.extract_secret(&c_1, &s_1, &c_2, &s_2), Some(secret));
+        assert_eq!(zkp.extract_secret(&c_1, &s_1, &c_1, &s_1), None);
+    }
+
+    #[test]
+    fn test_extract_secret_with_1024_bit_constants() {
+        let (alpha, beta, p, q) = ZKP::get_constants();
+        let zkp = ZKP {
+            prime: p.clone(),
+            order: q.clone(),
+            alpha: alpha.clone(),
+            beta: beta.clone(),
+        };
+
+        let x = ZKP::generate_random_number(&q);
+        let k = ZKP::generate_random_number(&q);
+        let c_1 = ZKP::generate_random_number(&q);
+        let c_2 = ZKP::generate_random_number(&q);
+
+        let s_1 = zkp.solve(&k, &c_1, &x);
+        let s_2 = zkp.solve(&k, &c_2, &x);
+
+        let extracted = zkp.extract_secret(&c_1, &s_1, &c_2, &s_2).unwrap();
+        assert_eq!(extracted, x);
+        assert_eq!(ZKP::exponentiate(&alpha, &extracted, &p), ZKP::exponentiate(&alpha, &x, &p));
+    }
+
+    #[test]
+    fn test_toy_example_with_external_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let x = BigUint::from(6_u32);
+        let (y_1, y_2) = (BigUint::from(2_u32), BigUint::from(3_u32));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let (k, r_1, r_2) = zkp.commitment_with_rng(&mut rng);
+        let c = ZKP::generate_random_number_with_rng(&zkp.order, &mut rng);
+        let s = zkp.solve(&k, &c, &x);
+        assert!(zkp.verify(&r_1, &r_2, &y_1, &y_2, &c, &s));
+
+        // the same seed gives the same commitment
+        let (_, again_1, again_2) = zkp.commitment_with_rng(&mut StdRng::seed_from_u64(7));
+        assert_eq!((again_1, again_2), (r_1, r_2));
+    }
+
+    /// builds the library without `std` for a bare-metal target, which has no `std` to fall back on
+    /// skips with a message if the target is not installed, unless ZKP_REQUIRE_NO_STD_TARGET is set as in CI
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_no_std_build() {
+        use std::{path::Path, process::Command};
+
+        const BARE_METAL_TARGET: &str = "thumbv7em-none-eabihf";
+
+        let installed = Command::new("rustc")
+            .args(["--print", "target-libdir", "--target", BARE_METAL_TARGET])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .is_some_and(|libdir| Path::new(&libdir).is_dir());
+        if !installed {
+            assert!(
+                std::env::var_os("ZKP_REQUIRE_NO_STD_TARGET").is_none(),
+                "{} is not installed: rustup target add {}",
+                BARE_METAL_TARGET,
+                BARE_METAL_TARGET
+            );
+            eprintln!("⚠ skipped the no_std build: {} is not installed (rustup target add {})", BARE_METAL_TARGET, BARE_METAL_TARGET);
+            return;
+        }
+
+        let target_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("target").join("no_std");
+        let output = Command::new(env!("CARGO"))
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .args(["build", "--offline", "--lib", "--no-default-features", "--target", BARE_METAL_TARGET, "--target-dir"])
+            .arg(&target_dir)
+            .output()
+            .expect("could not run cargo");
+        assert!(output.status.success(), "no_std build failed:\n{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    /// builds the library as a static archive, links tests/ffi_exchange.c against it and the
+    /// generated header, and runs the toy and 1024-bit exchanges
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn test_c_exchange() {
+        use std::{path::Path, process::Command};
+
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let target_dir = manifest_dir.join("target").join("ffi");
+
+        let output = Command::new(env!("CARGO"))
+            .current_dir(manifest_dir)
+            .args(["rustc", "--offline", "--lib", "--no-default-features", "--features", "ffi", "--crate-type", "staticlib", "--target-dir"])
+            .arg(&target_dir)
+            .args(["--", "--print", "native-static-libs"])
+            .output()
+            .expect("could not run cargo");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "static library build failed:\n{}", stderr);
+        // rustc only prints the system libraries when it actually links the archive
+        let native_libs = stderr
+            .lines()
+            .find_map(|line| line.split_once("native-static-libs: ").map(|(_, libs)| libs.to_string()))
+            .unwrap_or_else(|| "-lpthread -ldl -lm".to_string());
+
+        let program = target_dir.join("ffi_exchange");
+        let output = Command::new("cc")
+            .arg(manifest_dir.join("tests").join("ffi_exchange.c"))
+            .arg("-I")
+            .arg(manifest_dir.join("include"))
+            .arg(target_dir.join("debug").join("libzkp_chaum_pedersen.a"))
+            .args(native_libs.split_whitespace())
+            .arg("-o")
+            .arg(&program)
+            .output()
+            .expect("could not run cc");
+        assert!(output.status.success(), "cc failed:\n{}", String::from_utf8_lossy(&output.stderr));
+
+        let output = Command::new(&program).output().expect("could not run the C program");
+        print!("{}", String::from_utf8_lossy(&output.stdout));
+        assert!(output.status.success(), "C exchange failed:\n{}", String::from_utf8_lossy(&output.stderr));
+    }
+}
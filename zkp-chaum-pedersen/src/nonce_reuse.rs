@@ -0,0 +1,126 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::{String, ToString},
+};
+
+use num_bigint::BigUint;
+
+use crate::ZKP;
+
+/// one run of the protocol as seen on the wire
+/// (r1, r2) commitment, c challenge, s answer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub r1: BigUint,
+    pub r2: BigUint,
+    pub c: BigUint,
+    pub s: BigUint,
+}
+
+/// prover with a broken RNG: the same k is used for every login
+pub struct ReusingProver {
+    pub x: BigUint,
+    pub k: BigUint,
+}
+
+impl ReusingProver {
+    #[cfg(feature = "std")]
+    pub fn new(zkp: &ZKP, x: BigUint) -> Self {
+        let k = ZKP::generate_random_number(&zkp.order);
+        ReusingProver { x, k }
+    }
+
+    /// r1 = a^k, r2 = b^k with the fixed k
+    pub fn commitment(&self, zkp: &ZKP) -> (BigUint, BigUint) {
+        (
+            ZKP::exponentiate(&zkp.alpha, &self.k, &zkp.prime),
+            ZKP::exponentiate(&zkp.beta, &self.k, &zkp.prime),
+        )
+    }
+
+    /// s = k - c * x mod q with the fixed k
+    pub fn answer(&self, zkp: &ZKP, c: &BigUint) -> BigUint {
+        zkp.solve(&self.k, c, &self.x)
+    }
+}
+
+/// recovers x from two accepting transcripts if the prover reused k
+/// returns None if the commitments differ or the challenges coincide
+pub fn recover_secret(zkp: &ZKP, first: &Transcript, second: &Transcript) -> Option<BigUint> {
+    if first.r1 != second.r1 || first.r2 != second.r2 {
+        return None;
+    }
+    zkp.extract_secret(&first.c, &first.s, &second.c, &second.s)
+}
+
+/// server-side detector: remembers every (r1, r2) a user committed to
+#[derive(Debug, Default)]
+pub struct CommitmentLog {
+    seen: BTreeMap<String, BTreeSet<(BigUint, BigUint)>>,
+}
+
+impl CommitmentLog {
+    /// records the commitment and returns true if the user already sent it before
+    pub fn record(&mut self, user_name: &str, r1: &BigUint, r2: &BigUint) -> bool {
+        let commitments = self.seen.entry(user_name.to_string()).or_default();
+        !commitments.insert((r1.clone(), r2.clone()))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    fn toy_zkp() -> ZKP {
+        ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        }
+    }
+
+    fn login(zkp: &ZKP, prover: &ReusingProver, c: BigUint) -> Transcript {
+        let (r1, r2) = prover.commitment(zkp);
+        let s = prover.answer(zkp, &c);
+        Transcript { r1, r2, c, s }
+    }
+
+    #[test]
+    fn test_recover_secret_toy_example() {
+        let zkp = toy_zkp();
+        let x = BigUint::from(6_u32);
+        let prover = ReusingProver { x: x.clone(), k: BigUint::from(7_u32) };
+        let y1 = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+        let y2 = ZKP::exponentiate(&zkp.beta, &x, &zkp.prime);
+
+        let first = login(&zkp, &prover, BigUint::from(4_u32));
+        let second = login(&zkp, &prover, BigUint::from(10_u32));
+        assert!(zkp.verify(&first.r1, &first.r2, &y1, &y2, &first.c, &first.s));
+        assert!(zkp.verify(&second.r1, &second.r2, &y1, &y2, &second.c, &second.s));
+
+        assert_eq!(recover_secret(&zkp, &first, &second), Some(x));
+    }
+
+    #[test]
+    fn test_recover_secret_needs_same_commitment() {
+        let zkp = toy_zkp();
+        let x = BigUint::from(6_u32);
+        let first = login(&zkp, &ReusingProver { x: x.clone(), k: BigUint::from(7_u32) }, BigUint::from(4_u32));
+        let second = login(&zkp, &ReusingProver { x, k: BigUint::from(3_u32) }, BigUint::from(10_u32));
+
+        assert_eq!(recover_secret(&zkp, &first, &second), None);
+    }
+
+    #[test]
+    fn test_commitment_log_flags_reuse() {
+        let mut log = CommitmentLog::default();
+        let r1 = BigUint::from(8_u32);
+        let r2 = BigUint::from(4_u32);
+
+        assert!(!log.record("alice", &r1, &r2));
+        assert!(!log.record("bob", &r1, &r2));
+        assert!(log.record("alice", &r1, &r2));
+        assert!(!log.record("alice", &r2, &r1));
+    }
+}
@@ -0,0 +1,83 @@
+use num_bigint::BigUint;
+use zkp_chaum_pedersen::{
+    params,
+    nonce_reuse::{recover_secret, ReusingProver, Transcript},
+    ZKP,
+};
+
+pub mod zkp_auth {
+    include!("./zkp_auth.rs");
+}
+
+use zkp_auth::{auth_client::AuthClient, AuthenticationAnswerRequest, AuthenticationChallengeRequest, RegisterRequest};
+
+const STRING_SIZE: usize = 12;
+
+#[tokio::main]
+async fn main() {
+    let addr = "http://127.0.0.1:50051".to_string();
+    let mut client = AuthClient::connect(addr)
+        .await
+        .expect("could not connect to the server");
+    println!("☑ Connected client to the server.");
+
+    let zkp = params::from_args_or_default().expect("could not load the group parameters");
+
+    // victim registers with a random password the attacker never sees
+    let user_name = format!("victim-{}", ZKP::generate_random_string(6));
+    let password = ZKP::generate_random_string(STRING_SIZE);
+    let x = BigUint::from_bytes_be(password.as_bytes());
+    let prover = ReusingProver::new(&zkp, x.clone());
+
+    let request = RegisterRequest {
+        user: user_name.clone(),
+        y1: ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime).to_bytes_be(),
+        y2: ZKP::exponentiate(&zkp.beta, &x, &zkp.prime).to_bytes_be(),
+    };
+    client.register(request)
+        .await
+        .expect("could not register in server");
+    println!("☑ Registered user {}.", user_name);
+
+    // two logins with the same k, recorded by an eavesdropper
+    let mut transcripts = Vec::new();
+    for login in 1..=2 {
+        let (r1, r2) = prover.commitment(&zkp);
+        let request = AuthenticationChallengeRequest {
+            user: user_name.clone(),
+            r1: r1.to_bytes_be(),
+            r2: r2.to_bytes_be(),
+        };
+        let response = client.create_authentication_challenge(request)
+            .await
+            .expect("could not create challenge")
+            .into_inner();
+        let c = BigUint::from_bytes_be(&response.c);
+        let s = prover.answer(&zkp, &c);
+
+        let request = AuthenticationAnswerRequest {
+            auth_id: response.auth_id,
+            s: s.to_bytes_be(),
+        };
+        let response = client.verify_authentication(request)
+            .await
+            .expect("server could not verify authentication of user")
+            .into_inner();
+        println!("☑ Login {} succeeded with SessionId {}.", login, response.session_id);
+
+        transcripts.push(Transcript { r1, r2, c, s });
+    }
+
+    // attacker side: only the two transcripts are used from here on
+    let recovered = recover_secret(&zkp, &transcripts[0], &transcripts[1])
+        .expect("the two logins drew the same challenge, run the demo again");
+    let recovered_password = String::from_utf8_lossy(&recovered.to_bytes_be()).to_string();
+
+    println!("Recovered x = {}", recovered);
+    println!("Recovered password = {}", recovered_password);
+    if recovered_password == password {
+        println!("⚠ The password was leaked by reusing k across two logins.");
+    } else {
+        println!("☒ Recovery did not match the password.");
+    }
+}
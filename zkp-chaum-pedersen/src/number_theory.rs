@@ -0,0 +1,305 @@
+use alloc::vec::Vec;
+
+use num_bigint::{BigInt, BigUint, RandBigInt, Sign};
+use rand::RngCore;
+
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Miller-Rabin with `rounds` random bases, error probability at most 4^-rounds
+#[cfg(feature = "std")]
+pub fn is_probable_prime(n: &BigUint, rounds: usize) -> bool {
+    is_probable_prime_with_rng(n, rounds, &mut rand::thread_rng())
+}
+
+/// `is_probable_prime` with the caller's RNG for the bases
+pub fn is_probable_prime_with_rng<R: RngCore + ?Sized>(n: &BigUint, rounds: usize, rng: &mut R) -> bool {
+    let zero = BigUint::from(0u32);
+    let one = BigUint::from(1u32);
+    let two = BigUint::from(2u32);
+
+    for small_prime in SMALL_PRIMES {
+        let small_prime = BigUint::from(small_prime);
+        if *n == small_prime {
+            return true;
+        }
+        if n % &small_prime == zero {
+            return false;
+        }
+    }
+    if *n < two {
+        return false;
+    }
+
+    // n - 1 = d * 2^s with d odd
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().expect("n - 1 is not zero");
+    let d = &n_minus_one >> s;
+
+    'witness: for _ in 0..rounds {
+        let a = rng.gen_biguint_range(&two, &n_minus_one);
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = x.modpow(&two, n);
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// (g, u, v) with g = gcd(a, b) = u a + v b
+pub fn extended_gcd(a: &BigUint, b: &BigUint) -> (BigUint, BigInt, BigInt) {
+    let (mut old_r, mut r) = (BigInt::from(a.clone()), BigInt::from(b.clone()));
+    let (mut old_u, mut u) = (BigInt::from(1), BigInt::from(0));
+    let (mut old_v, mut v) = (BigInt::from(0), BigInt::from(1));
+
+    while r.sign() != Sign::NoSign {
+        let quotient = &old_r / &r;
+        (old_r, r) = (r.clone(), old_r - &quotient * r);
+        (old_u, u) = (u.clone(), old_u - &quotient * u);
+        (old_v, v) = (v.clone(), old_v - &quotient * v);
+    }
+    (old_r.magnitude().clone(), old_u, old_v)
+}
+
+/// a^-1 mod m, None if gcd(a, m) != 1
+pub fn mod_inverse(a: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    let (g, u, _) = extended_gcd(&(a % modulus), modulus);
+    if g != BigUint::from(1u32) {
+        return None;
+    }
+    let modulus = BigInt::from(modulus.clone());
+    (((u % &modulus) + &modulus) % &modulus).to_biguint()
+}
+
+/// x with x = r_i mod m_i for pairwise coprime m_i, reduced mod prod m_i
+/// returns None if the moduli are not pairwise coprime
+pub fn crt(congruences: &[(BigUint, BigUint)]) -> Option<BigUint> {
+    let mut x = BigUint::from(0u32);
+    let mut modulus = BigUint::from(1u32);
+    for (residue, m) in congruences {
+        // x + modulus * t = residue mod m
+        let inverse = mod_inverse(&modulus, m)?;
+        let difference = (residue % m + m - &x % m) % m;
+        let t = (difference * inverse) % m;
+        x += &modulus * t;
+        modulus *= m;
+    }
+    Some(x % modulus)
+}
+
+/// prime factors of n with multiplicity as (prime, exponent), by trial division
+/// only meant for the small numbers of toy groups
+pub fn factorize(n: &BigUint) -> Vec<(BigUint, u32)> {
+    let zero = BigUint::from(0u32);
+    let mut n = n.clone();
+    let mut factors = Vec::new();
+    let mut divisor = BigUint::from(2u32);
+    while &divisor * &divisor <= n {
+        let mut exponent = 0;
+        while &n % &divisor == zero {
+            n /= &divisor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((divisor.clone(), exponent));
+        }
+        divisor += 1u32;
+    }
+    if n > BigUint::from(1u32) {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// smallest d > 0 with a^d = 1 mod p, given the factorization of a multiple of it (e.g. p - 1)
+/// returns None if a is not invertible mod p
+pub fn multiplicative_order_with(a: &BigUint, modulus: &BigUint, multiple: &BigUint, factors: &[(BigUint, u32)]) -> Option<BigUint> {
+    let one = BigUint::from(1u32);
+    if a.modpow(multiple, modulus) != one {
+        return None;
+    }
+    let mut order = multiple.clone();
+    for (prime, exponent) in factors {
+        for _ in 0..*exponent {
+            let candidate = &order / prime;
+            if a.modpow(&candidate, modulus) != one {
+                break;
+            }
+            order = candidate;
+        }
+    }
+    Some(order)
+}
+
+/// order of a in Z_p^* for a prime p, factoring p - 1 by trial division
+pub fn multiplicative_order(a: &BigUint, prime: &BigUint) -> Option<BigUint> {
+    let group_order = prime - 1u32;
+    multiplicative_order_with(a, prime, &group_order, &factorize(&group_order))
+}
+
+/// a generator of the subgroup of order d of Z_p^* for a prime p and d | p - 1:
+/// h^((p - 1) / d) for the first h = 2, 3, ... where it has order exactly d,
+/// not necessarily the smallest generator (4 rather than 2 for p = 23, d = 11)
+/// factors d by trial division, so d must be small or have only small factors
+pub fn subgroup_generator(prime: &BigUint, subgroup_order: &BigUint) -> Option<BigUint> {
+    let zero = BigUint::from(0u32);
+    let group_order = prime - 1u32;
+    if *subgroup_order == zero || &group_order % subgroup_order != zero {
+        return None;
+    }
+    let cofactor = &group_order / subgroup_order;
+    let factors = factorize(subgroup_order);
+
+    let mut h = BigUint::from(2u32);
+    while h < *prime {
+        let candidate = h.modpow(&cofactor, prime);
+        if multiplicative_order_with(&candidate, prime, subgroup_order, &factors).as_ref() == Some(subgroup_order) {
+            return Some(candidate);
+        }
+        h += 1u32;
+    }
+    (*subgroup_order == BigUint::from(1u32)).then(|| BigUint::from(1u32))
+}
+
+/// smallest generator of the whole group Z_p^*
+pub fn primitive_root(prime: &BigUint) -> Option<BigUint> {
+    subgroup_generator(prime, &(prime - 1u32))
+}
+
+/// (a / n) for odd n > 0, in {-1, 0, 1}
+pub fn jacobi_symbol(a: &BigUint, n: &BigUint) -> i8 {
+    let zero = BigUint::from(0u32);
+    let one = BigUint::from(1u32);
+    assert!(n.bit(0), "the Jacobi symbol needs an odd modulus");
+
+    let mut a = a % n;
+    let mut n = n.clone();
+    let mut result = 1;
+    while a != zero {
+        while !a.bit(0) {
+            a >>= 1;
+            let n_mod_8 = (&n % 8u32).to_u32_digits().first().copied().unwrap_or(0);
+            if n_mod_8 == 3 || n_mod_8 == 5 {
+                result = -result;
+            }
+        }
+        core::mem::swap(&mut a, &mut n);
+        if &a % 4u32 == BigUint::from(3u32) && &n % 4u32 == BigUint::from(3u32) {
+            result = -result;
+        }
+        a %= &n;
+    }
+    if n == one { result } else { 0 }
+}
+
+/// (a / p) for an odd prime p: 1 for quadratic residues, -1 for non-residues, 0 if p | a
+pub fn legendre_symbol(a: &BigUint, prime: &BigUint) -> i8 {
+    let exponent = (prime - 1u32) >> 1;
+    let value = a.modpow(&exponent, prime);
+    if value == BigUint::from(0u32) {
+        0
+    } else if value == BigUint::from(1u32) {
+        1
+    } else {
+        -1
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+    use crate::ZKP;
+
+    fn toy_zkp() -> ZKP {
+        ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        }
+    }
+
+    fn big(n: u32) -> BigUint {
+        BigUint::from(n)
+    }
+
+    #[test]
+    fn test_miller_rabin() {
+        let primes = [2_u32, 3, 11, 23, 97, 101, 7919, 2_147_483_647];
+        let composites = [0_u32, 1, 4, 9, 561, 1105, 7917, 2_147_483_649];
+        for prime in primes {
+            assert!(is_probable_prime(&big(prime), 20), "{} is prime", prime);
+        }
+        for composite in composites {
+            assert!(!is_probable_prime(&big(composite), 20), "{} is composite", composite);
+        }
+
+        let (_, _, p, q) = ZKP::get_constants();
+        assert!(is_probable_prime(&p, 20));
+        assert!(is_probable_prime(&q, 20));
+        assert!(!is_probable_prime(&(&p * &q), 20));
+    }
+
+    #[test]
+    fn test_extended_gcd_and_inverse() {
+        let (g, u, v) = extended_gcd(&big(240), &big(46));
+        assert_eq!(g, big(2));
+        assert_eq!(u * 240 + v * 46, BigInt::from(2));
+
+        let zkp = toy_zkp();
+        // 4 * 6 = 24 = 1 mod 23 and 7 * 8 = 56 = 1 mod 11
+        assert_eq!(mod_inverse(&zkp.alpha, &zkp.prime), Some(big(6)));
+        assert_eq!(mod_inverse(&big(7), &zkp.order), Some(big(8)));
+        assert_eq!(mod_inverse(&big(22), &big(11)), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        // x = 2 mod 3, 3 mod 5, 2 mod 7 gives x = 23, the toy prime
+        let congruences = [(big(2), big(3)), (big(3), big(5)), (big(2), big(7))];
+        assert_eq!(crt(&congruences), Some(big(23)));
+        assert_eq!(crt(&[(big(1), big(4)), (big(1), big(6))]), None);
+    }
+
+    #[test]
+    fn test_orders_and_generators_of_toy_group() {
+        let zkp = toy_zkp();
+        assert_eq!(factorize(&(&zkp.prime - 1u32)), vec![(big(2), 1), (big(11), 1)]);
+
+        assert_eq!(multiplicative_order(&zkp.alpha, &zkp.prime), Some(zkp.order.clone()));
+        assert_eq!(multiplicative_order(&zkp.beta, &zkp.prime), Some(zkp.order.clone()));
+        assert_eq!(multiplicative_order(&big(22), &zkp.prime), Some(big(2)));
+        assert_eq!(multiplicative_order(&big(5), &zkp.prime), Some(big(22)));
+
+        assert_eq!(primitive_root(&zkp.prime), Some(big(5)));
+        let generator = subgroup_generator(&zkp.prime, &zkp.order).unwrap();
+        assert_eq!(generator, zkp.alpha);
+        assert_eq!(subgroup_generator(&zkp.prime, &big(2)), Some(big(22)));
+        assert_eq!(subgroup_generator(&zkp.prime, &big(5)), None);
+    }
+
+    #[test]
+    fn test_legendre_and_jacobi_symbols() {
+        let zkp = toy_zkp();
+        // the order-11 subgroup of Z_23^* are exactly the quadratic residues
+        for a in 1..23_u32 {
+            let in_subgroup = zkp.is_group_element(&big(a));
+            assert_eq!(legendre_symbol(&big(a), &zkp.prime), if in_subgroup { 1 } else { -1 });
+            assert_eq!(jacobi_symbol(&big(a), &zkp.prime), legendre_symbol(&big(a), &zkp.prime));
+        }
+        assert_eq!(legendre_symbol(&big(46), &zkp.prime), 0);
+
+        // (2 / 15) = (2 / 3)(2 / 5) = 1 although 2 is not a square mod 15
+        assert_eq!(jacobi_symbol(&big(2), &big(15)), 1);
+        assert_eq!(jacobi_symbol(&big(7), &big(15)), -1);
+        assert_eq!(jacobi_symbol(&big(5), &big(15)), 0);
+    }
+}
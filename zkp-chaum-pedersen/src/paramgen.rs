@@ -0,0 +1,159 @@
+use num_bigint::{BigUint, RandBigInt};
+
+use crate::{number_theory::is_probable_prime, ZKP};
+
+pub const DEFAULT_MILLER_RABIN_ROUNDS: usize = 40;
+
+/// candidates for p tried per bit of p before `generate` gives up on q
+const P_ATTEMPTS_PER_BIT: u64 = 8;
+
+/// requested sizes of a new Schnorr group
+/// with `safe_prime` p = 2q + 1 and q_bits is ignored (q has p_bits - 1 bits)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamGenConfig {
+    pub p_bits: u64,
+    pub q_bits: u64,
+    pub rounds: usize,
+    pub safe_prime: bool,
+}
+
+impl Default for ParamGenConfig {
+    /// same sizes as the 1024-bit group of `ZKP::get_constants`
+    fn default() -> Self {
+        ParamGenConfig {
+            p_bits: 1024,
+            q_bits: 160,
+            rounds: DEFAULT_MILLER_RABIN_ROUNDS,
+            safe_prime: false,
+        }
+    }
+}
+
+/// random integer with exactly `bits` bits
+fn random_with_bits(bits: u64) -> BigUint {
+    let mut rng = rand::thread_rng();
+    let mut candidate = rng.gen_biguint(bits);
+    candidate.set_bit(bits - 1, true);
+    candidate
+}
+
+/// random prime with exactly `bits` bits
+pub fn random_prime(bits: u64, rounds: usize) -> BigUint {
+    assert!(bits >= 2, "a prime has at least 2 bits");
+    loop {
+        let mut candidate = random_with_bits(bits);
+        if bits > 2 {
+            candidate.set_bit(0, true);
+        }
+        if is_probable_prime(&candidate, rounds) {
+            return candidate;
+        }
+    }
+}
+
+/// alpha = h^((p - 1) / q) != 1 for h = 2, 3, ... and beta = alpha^e for a random e != 0
+/// q is prime, so any element of the subgroup other than 1 generates it
+fn generators(prime: &BigUint, order: &BigUint) -> (BigUint, BigUint) {
+    let one = BigUint::from(1u32);
+    let cofactor = (prime - &one) / order;
+
+    let mut h = BigUint::from(2u32);
+    let alpha = loop {
+        let candidate = h.modpow(&cofactor, prime);
+        if candidate != one {
+            break candidate;
+        }
+        h += 1u32;
+    };
+    let exponent = ZKP::generate_random_number(&(order - &one)) + &one;
+    let beta = alpha.modpow(&exponent, prime);
+    (alpha, beta)
+}
+
+/// new group with q | p - 1, alpha and beta of order q
+pub fn generate(config: &ParamGenConfig) -> ZKP {
+    if config.safe_prime {
+        return generate_safe(config.p_bits, config.rounds);
+    }
+    assert!(config.q_bits >= 2 && config.q_bits < config.p_bits, "q must have fewer bits than p");
+
+    let one = BigUint::from(1u32);
+    let (prime, order) = 'search: loop {
+        let order = random_prime(config.q_bits, config.rounds);
+        let two_q = &order << 1;
+        // a q with q_bits close to p_bits leaves few candidates for p, so draw a new q after a while
+        for _ in 0..P_ATTEMPTS_PER_BIT * config.p_bits {
+            // p = X - (X mod 2q) + 1 is 1 mod 2q
+            let x = random_with_bits(config.p_bits);
+            let candidate: BigUint = &x - (&x % &two_q) + &one;
+            if candidate.bits() == config.p_bits && is_probable_prime(&candidate, config.rounds) {
+                break 'search (candidate, order);
+            }
+        }
+    };
+    let (alpha, beta) = generators(&prime, &order);
+    ZKP { prime, order, alpha, beta }
+}
+
+/// new group with p = 2q + 1, the subgroup of order q are the quadratic residues
+pub fn generate_safe(p_bits: u64, rounds: usize) -> ZKP {
+    assert!(p_bits >= 3, "a safe prime has at least 3 bits");
+    let one = BigUint::from(1u32);
+    loop {
+        let order = random_prime(p_bits - 1, rounds);
+        let prime = (&order << 1) + &one;
+        if is_probable_prime(&prime, rounds) {
+            let (alpha, beta) = generators(&prime, &order);
+            return ZKP { prime, order, alpha, beta };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_generate_schnorr_group() {
+        let config = ParamGenConfig { p_bits: 128, q_bits: 48, rounds: 20, safe_prime: false };
+        let zkp = generate(&config);
+
+        assert_eq!(zkp.prime.bits(), 128);
+        assert_eq!(zkp.order.bits(), 48);
+        assert_eq!((&zkp.prime - 1u32) % &zkp.order, BigUint::from(0_u32));
+        assert!(zkp.is_group_element(&zkp.alpha) && zkp.alpha != BigUint::from(1_u32));
+        assert!(zkp.is_group_element(&zkp.beta) && zkp.beta != BigUint::from(1_u32));
+
+        let x = ZKP::generate_random_number(&zkp.order);
+        let k = ZKP::generate_random_number(&zkp.order);
+        let c = ZKP::generate_random_number(&zkp.order);
+        let y_1 = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+        let y_2 = ZKP::exponentiate(&zkp.beta, &x, &zkp.prime);
+        let r_1 = ZKP::exponentiate(&zkp.alpha, &k, &zkp.prime);
+        let r_2 = ZKP::exponentiate(&zkp.beta, &k, &zkp.prime);
+        let s = zkp.solve(&k, &c, &x);
+        assert!(zkp.verify(&r_1, &r_2, &y_1, &y_2, &c, &s));
+    }
+
+    #[test]
+    fn test_generate_safe_prime_group() {
+        let config = ParamGenConfig { p_bits: 64, safe_prime: true, ..Default::default() };
+        let zkp = generate(&config);
+
+        assert_eq!(zkp.prime.bits(), 64);
+        assert_eq!(zkp.prime, (&zkp.order << 1) + 1u32);
+        assert!(is_probable_prime(&zkp.order, 20));
+        assert!(zkp.is_group_element(&zkp.alpha) && zkp.alpha != BigUint::from(1_u32));
+    }
+
+    #[test]
+    fn test_generate_with_q_one_bit_shorter_than_p() {
+        // only p = 2q + 1 fits, most q have no p and must be redrawn
+        let config = ParamGenConfig { p_bits: 64, q_bits: 63, rounds: 20, safe_prime: false };
+        let zkp = generate(&config);
+
+        assert_eq!(zkp.prime.bits(), 64);
+        assert_eq!(zkp.order.bits(), 63);
+        assert_eq!(zkp.prime, (&zkp.order << 1) + 1u32);
+    }
+}
@@ -0,0 +1,243 @@
+use std::{fmt, fs, io, path::Path};
+
+use num_bigint::BigUint;
+
+use crate::{
+    dhparam::{self, Format},
+    fips186::VerifiableParameters,
+    groups, number_theory, paramgen, ZKP,
+};
+
+const HEADER: &str = "# zkp-chaum-pedersen parameters";
+
+#[derive(Debug)]
+pub enum ParamError {
+    Io(io::Error),
+    MissingValue(&'static str),
+    InvalidLine(String),
+    InvalidHex(&'static str),
+    InvalidGroup(&'static str),
+    InvalidEncoding(&'static str),
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamError::Io(error) => write!(f, "could not access parameter file: {}", error),
+            ParamError::MissingValue(name) => write!(f, "parameter {} is missing", name),
+            ParamError::InvalidLine(line) => write!(f, "could not parse line: {}", line),
+            ParamError::InvalidHex(name) => write!(f, "parameter {} is not valid hex", name),
+            ParamError::InvalidGroup(reason) => write!(f, "invalid group: {}", reason),
+            ParamError::InvalidEncoding(reason) => write!(f, "invalid encoding: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+impl From<io::Error> for ParamError {
+    fn from(error: io::Error) -> Self {
+        ParamError::Io(error)
+    }
+}
+
+/// p, q prime, q | p - 1, alpha and beta of order q
+pub fn validate(zkp: &ZKP, rounds: usize) -> Result<(), ParamError> {
+    let one = BigUint::from(1u32);
+    if !number_theory::is_probable_prime(&zkp.prime, rounds) {
+        return Err(ParamError::InvalidGroup("p is not prime"));
+    }
+    if !number_theory::is_probable_prime(&zkp.order, rounds) {
+        return Err(ParamError::InvalidGroup("q is not prime"));
+    }
+    if (&zkp.prime - &one) % &zkp.order != BigUint::from(0u32) {
+        return Err(ParamError::InvalidGroup("q does not divide p - 1"));
+    }
+    if !zkp.is_group_element(&zkp.alpha) || zkp.alpha == one {
+        return Err(ParamError::InvalidGroup("alpha does not have order q"));
+    }
+    if !zkp.is_group_element(&zkp.beta) || zkp.beta == one {
+        return Err(ParamError::InvalidGroup("beta does not have order q"));
+    }
+    Ok(())
+}
+
+/// one `name = hex` line per value
+pub fn to_string(zkp: &ZKP) -> String {
+    format!(
+        "{}\np = {}\nq = {}\nalpha = {}\nbeta = {}\n",
+        HEADER,
+        hex::encode_upper(zkp.prime.to_bytes_be()),
+        hex::encode_upper(zkp.order.to_bytes_be()),
+        hex::encode_upper(zkp.alpha.to_bytes_be()),
+        hex::encode_upper(zkp.beta.to_bytes_be()),
+    )
+}
+
+/// `to_string` plus the FIPS 186-4 seed and counter the group can be re-derived from
+pub fn to_string_with_seed(parameters: &VerifiableParameters) -> Result<String, ParamError> {
+    Ok(format!(
+        "{}domain_parameter_seed = {}\ncounter = {:08X}\n",
+        to_string(&parameters.to_zkp()?),
+        hex::encode_upper(&parameters.domain_parameter_seed),
+        parameters.counter,
+    ))
+}
+
+/// parses the format of `to_string` and validates the group
+/// if a domain parameter seed is present, p, q, alpha and beta must re-derive from it
+pub fn from_str(text: &str) -> Result<ZKP, ParamError> {
+    let mut values: [Option<Vec<u8>>; 6] = Default::default();
+    let names = ["p", "q", "alpha", "beta", "domain_parameter_seed", "counter"];
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| ParamError::InvalidLine(line.to_string()))?;
+        let position = names
+            .iter()
+            .position(|known| *known == name.trim())
+            .ok_or_else(|| ParamError::InvalidLine(line.to_string()))?;
+        let bytes = hex::decode(value.trim()).map_err(|_| ParamError::InvalidHex(names[position]))?;
+        values[position] = Some(bytes);
+    }
+
+    let [prime, order, alpha, beta, seed, counter] = values;
+    let integer = |value: Option<Vec<u8>>, name| {
+        value.map(|bytes| BigUint::from_bytes_be(&bytes)).ok_or(ParamError::MissingValue(name))
+    };
+    let zkp = ZKP {
+        prime: integer(prime, "p")?,
+        order: integer(order, "q")?,
+        alpha: integer(alpha, "alpha")?,
+        beta: integer(beta, "beta")?,
+    };
+    validate(&zkp, paramgen::DEFAULT_MILLER_RABIN_ROUNDS)?;
+
+    if let Some(seed) = seed {
+        let counter = u32::try_from(integer(counter, "counter")?)
+            .map_err(|_| ParamError::InvalidGroup("counter is larger than 4L - 1"))?;
+        let parameters = VerifiableParameters {
+            prime: zkp.prime.clone(),
+            order: zkp.order.clone(),
+            domain_parameter_seed: seed,
+            counter,
+        };
+        parameters.verify(paramgen::DEFAULT_MILLER_RABIN_ROUNDS)?;
+        parameters.verify_generators(&zkp)?;
+    }
+    Ok(zkp)
+}
+
+pub fn save<P: AsRef<Path>>(zkp: &ZKP, path: P) -> Result<(), ParamError> {
+    fs::write(path, to_string(zkp))?;
+    Ok(())
+}
+
+/// reads the text format of `to_string`, or PKCS#3 / X9.42 parameters as PEM or DER
+pub fn load<P: AsRef<Path>>(path: P) -> Result<ZKP, ParamError> {
+    let bytes = fs::read(path)?;
+    if bytes.first() == Some(&0x30) {
+        return dhparam::from_der(&bytes, Format::X942)
+            .or_else(|error| dhparam::from_der(&bytes, Format::Pkcs3).map_err(|_| error));
+    }
+    let text = String::from_utf8(bytes).map_err(|_| ParamError::InvalidEncoding("parameter file is neither text nor DER"))?;
+    if text.trim_start().starts_with("-----BEGIN") {
+        dhparam::from_pem(&text)
+    } else {
+        from_str(&text)
+    }
+}
+
+/// value following `flag` on the command line, if any
+pub fn arg_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != flag);
+    args.next()?;
+    args.next()
+}
+
+/// value of `--params <file>` on the command line, if any
+pub fn path_from_args() -> Option<String> {
+    arg_value("--params")
+}
+
+/// group from the `--params <file>` or `--group <name>` argument,
+/// or the 1024-bit group of `ZKP::get_constants`
+pub fn from_args_or_default() -> Result<ZKP, ParamError> {
+    if let Some(path) = path_from_args() {
+        return load(path);
+    }
+    match arg_value("--group") {
+        Some(name) => groups::by_name(&name)
+            .ok_or(ParamError::InvalidGroup("unknown group name"))?
+            .zkp(),
+        None => Ok(ZKP::default()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_1024_bit_constants() {
+        let zkp = ZKP::default();
+        let text = to_string(&zkp);
+        assert_eq!(from_str(&text).unwrap(), zkp);
+    }
+
+    #[test]
+    fn test_save_and_load_generated_group() {
+        let config = paramgen::ParamGenConfig { p_bits: 96, q_bits: 32, rounds: 20, safe_prime: false };
+        let zkp = paramgen::generate(&config);
+        let path = std::env::temp_dir().join(format!("zkp-params-{}.txt", ZKP::generate_random_string(8)));
+
+        save(&zkp, &path).unwrap();
+        let loaded = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, zkp);
+    }
+
+    #[test]
+    fn test_load_pem_and_der() {
+        let zkp = ZKP::default();
+        let zkp = ZKP { beta: dhparam::derive_beta(&zkp.prime, &zkp.order, &zkp.alpha), ..zkp };
+        let path = std::env::temp_dir().join(format!("zkp-params-{}.der", ZKP::generate_random_string(8)));
+
+        fs::write(&path, dhparam::to_pem(&zkp, Format::X942)).unwrap();
+        assert_eq!(load(&path).unwrap(), zkp);
+        fs::write(&path, dhparam::to_der(&zkp, Format::X942)).unwrap();
+        assert_eq!(load(&path).unwrap(), zkp);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_with_domain_parameter_seed() {
+        let group = groups::by_name("fips186-1024-160").unwrap();
+        let zkp = group.zkp().unwrap();
+        let parameters = VerifiableParameters {
+            prime: zkp.prime.clone(),
+            order: zkp.order.clone(),
+            domain_parameter_seed: hex::decode("195CF324CDF98E81920496B4647004708400F2B5EBB7A6D62CDC36BBCFAED599").unwrap(),
+            counter: 583,
+        };
+
+        let text = to_string_with_seed(&parameters).unwrap();
+        assert_eq!(from_str(&text).unwrap(), zkp);
+        let tampered = text.replace("counter = 00000247", "counter = 00000248");
+        assert!(matches!(from_str(&tampered), Err(ParamError::InvalidGroup(_))));
+    }
+
+    #[test]
+    fn test_rejects_invalid_groups() {
+        let zkp = ZKP { alpha: BigUint::from(2_u32), ..Default::default() };
+        assert!(matches!(from_str(&to_string(&zkp)), Err(ParamError::InvalidGroup(_))));
+
+        assert!(matches!(from_str("p = 17\nq = 0B\nalpha = 04"), Err(ParamError::MissingValue("beta"))));
+        assert!(matches!(from_str("p = xyz"), Err(ParamError::InvalidHex("p"))));
+        assert!(matches!(from_str("gamma = 01"), Err(ParamError::InvalidLine(_))));
+    }
+}
@@ -0,0 +1,150 @@
+use alloc::vec::Vec;
+
+use num_bigint::BigUint;
+use rand::RngCore;
+
+use crate::{hash, ZKP};
+
+const GENERATOR_DST: &[u8] = b"zkp-chaum-pedersen/pedersen/generator";
+const OPENING_PROOF_DST: &[u8] = b"zkp-chaum-pedersen/pedersen/opening-proof";
+
+/// Sigma-protocol proof of knowledge of (m, r) with C = alpha^m h^r
+/// t = alpha^a h^b, e = H(..., t), s_m = a - e m, s_r = b - e r mod q
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpeningProof {
+    pub t: BigUint,
+    pub s_m: BigUint,
+    pub s_r: BigUint,
+}
+
+/// h = H(seed) in the order-q subgroup
+/// anyone can re-derive it from the public seed, and nobody knows log_alpha(h)
+pub fn generator(zkp: &ZKP, seed: &[u8]) -> BigUint {
+    hash::hash_to_group(zkp, GENERATOR_DST, &[seed])
+}
+
+/// C = alpha^m h^r mod p
+pub fn commit(zkp: &ZKP, h: &BigUint, m: &BigUint, r: &BigUint) -> BigUint {
+    (ZKP::exponentiate(&zkp.alpha, m, &zkp.prime) * ZKP::exponentiate(h, r, &zkp.prime)) % &zkp.prime
+}
+
+/// commits to m with fresh randomness
+/// returns (C, r)
+#[cfg(feature = "std")]
+pub fn commit_random(zkp: &ZKP, h: &BigUint, m: &BigUint) -> (BigUint, BigUint) {
+    commit_random_with_rng(zkp, h, m, &mut rand::thread_rng())
+}
+
+/// `commit_random` with the caller's RNG
+pub fn commit_random_with_rng<R: RngCore + ?Sized>(zkp: &ZKP, h: &BigUint, m: &BigUint, rng: &mut R) -> (BigUint, BigUint) {
+    let r = ZKP::generate_random_number_with_rng(&zkp.order, rng);
+    (commit(zkp, h, m, &r), r)
+}
+
+pub fn verify_opening(zkp: &ZKP, h: &BigUint, c: &BigUint, m: &BigUint, r: &BigUint) -> bool {
+    zkp.is_group_element(c) && commit(zkp, h, m, r) == *c
+}
+
+/// C1 * C2 commits to m1 + m2 with randomness r1 + r2
+pub fn add(zkp: &ZKP, c_1: &BigUint, c_2: &BigUint) -> BigUint {
+    (c_1 * c_2) % &zkp.prime
+}
+
+/// opening of C1 * C2 given the openings of C1 and C2
+/// returns (m1 + m2, r1 + r2) mod q
+pub fn add_openings(zkp: &ZKP, opening_1: (&BigUint, &BigUint), opening_2: (&BigUint, &BigUint)) -> (BigUint, BigUint) {
+    (
+        (opening_1.0 + opening_2.0) % &zkp.order,
+        (opening_1.1 + opening_2.1) % &zkp.order,
+    )
+}
+
+fn opening_challenge(zkp: &ZKP, h: &BigUint, c: &BigUint, t: &BigUint) -> BigUint {
+    let parts = [&zkp.prime, &zkp.order, &zkp.alpha, h, c, t].map(|value| value.to_bytes_be());
+    let parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+    hash::hash_to_scalar(zkp, OPENING_PROOF_DST, &parts)
+}
+
+/// proves knowledge of the opening (m, r) of C without revealing it
+#[cfg(feature = "std")]
+pub fn prove_opening(zkp: &ZKP, h: &BigUint, c: &BigUint, m: &BigUint, r: &BigUint) -> OpeningProof {
+    prove_opening_with_rng(zkp, h, c, m, r, &mut rand::thread_rng())
+}
+
+/// `prove_opening` with the caller's RNG for the nonces (a, b)
+pub fn prove_opening_with_rng<R: RngCore + ?Sized>(zkp: &ZKP, h: &BigUint, c: &BigUint, m: &BigUint, r: &BigUint, rng: &mut R) -> OpeningProof {
+    let a = ZKP::generate_random_number_with_rng(&zkp.order, rng);
+    let b = ZKP::generate_random_number_with_rng(&zkp.order, rng);
+    let t = commit(zkp, h, &a, &b);
+    let e = opening_challenge(zkp, h, c, &t);
+    OpeningProof {
+        t,
+        s_m: zkp.solve(&a, &e, m),
+        s_r: zkp.solve(&b, &e, r),
+    }
+}
+
+/// t == alpha^s_m h^s_r C^e
+pub fn verify_opening_proof(zkp: &ZKP, h: &BigUint, c: &BigUint, proof: &OpeningProof) -> bool {
+    if !zkp.is_group_element(c) {
+        return false;
+    }
+    let e = opening_challenge(zkp, h, c, &proof.t);
+    let expected = (commit(zkp, h, &proof.s_m, &proof.s_r) * ZKP::exponentiate(c, &e, &zkp.prime)) % &zkp.prime;
+    proof.t == expected
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_commitment_toy_example() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let h = BigUint::from(9_u32);
+
+        // 4^6 * 9^7 = 2 * 4 = 8 mod 23
+        let c = commit(&zkp, &h, &BigUint::from(6_u32), &BigUint::from(7_u32));
+        assert_eq!(c, BigUint::from(8_u32));
+        assert!(verify_opening(&zkp, &h, &c, &BigUint::from(6_u32), &BigUint::from(7_u32)));
+        assert!(!verify_opening(&zkp, &h, &c, &BigUint::from(5_u32), &BigUint::from(7_u32)));
+    }
+
+    #[test]
+    fn test_homomorphic_addition_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let h = generator(&zkp, b"credentials v1");
+
+        let m_1 = BigUint::from(20_u32);
+        let m_2 = BigUint::from(22_u32);
+        let (c_1, r_1) = commit_random(&zkp, &h, &m_1);
+        let (c_2, r_2) = commit_random(&zkp, &h, &m_2);
+
+        let c = add(&zkp, &c_1, &c_2);
+        let (m, r) = add_openings(&zkp, (&m_1, &r_1), (&m_2, &r_2));
+        assert_eq!(m, BigUint::from(42_u32));
+        assert!(verify_opening(&zkp, &h, &c, &m, &r));
+    }
+
+    #[test]
+    fn test_opening_proof_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let h = generator(&zkp, b"credentials v1");
+
+        let m = ZKP::generate_random_number(&zkp.order);
+        let (c, r) = commit_random(&zkp, &h, &m);
+        let proof = prove_opening(&zkp, &h, &c, &m, &r);
+        assert!(verify_opening_proof(&zkp, &h, &c, &proof));
+
+        let (other_c, _) = commit_random(&zkp, &h, &m);
+        assert!(!verify_opening_proof(&zkp, &h, &other_c, &proof));
+        assert_ne!(h, generator(&zkp, b"credentials v2"));
+    }
+}
@@ -0,0 +1,134 @@
+use alloc::vec::Vec;
+
+use num_bigint::BigUint;
+
+use crate::{hash, ZKP};
+
+const NONCE_DST: &[u8] = b"zkp-chaum-pedersen/schnorr/nonce";
+const CHALLENGE_DST: &[u8] = b"zkp-chaum-pedersen/schnorr/challenge";
+
+/// (e, s) with e = H(alpha^k, y, msg) and s = k - e x mod q
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    pub e: BigUint,
+    pub s: BigUint,
+}
+
+/// k = H(x, msg) mod q, deterministic so that signing never depends on the RNG
+/// (a repeated k with different messages would leak x)
+fn nonce(zkp: &ZKP, x: &BigUint, msg: &[u8]) -> BigUint {
+    let x_bytes = x.to_bytes_be();
+    let mut attempt = 0u32;
+    loop {
+        let attempt_bytes = attempt.to_be_bytes();
+        let k = hash::hash_to_scalar(zkp, NONCE_DST, &[&x_bytes, msg, &attempt_bytes]);
+        if k != BigUint::from(0u32) {
+            return k;
+        }
+        attempt += 1;
+    }
+}
+
+/// e = H(p, q, alpha, y, r, msg) mod q
+fn challenge(zkp: &ZKP, y: &BigUint, r: &BigUint, msg: &[u8]) -> BigUint {
+    let parts = [&zkp.prime, &zkp.order, &zkp.alpha, y, r].map(|value| value.to_bytes_be());
+    let mut parts: Vec<&[u8]> = parts.iter().map(|part| part.as_slice()).collect();
+    parts.push(msg);
+    hash::hash_to_scalar(zkp, CHALLENGE_DST, &parts)
+}
+
+/// signs msg with the registered secret x of y = alpha^x
+pub fn sign(zkp: &ZKP, x: &BigUint, msg: &[u8]) -> Signature {
+    let y = ZKP::exponentiate(&zkp.alpha, x, &zkp.prime);
+    let k = nonce(zkp, x, msg);
+    let r = ZKP::exponentiate(&zkp.alpha, &k, &zkp.prime);
+    let e = challenge(zkp, &y, &r, msg);
+    let s = zkp.solve(&k, &e, x);
+    Signature { e, s }
+}
+
+/// r = alpha^s y^e and e == H(p, q, alpha, y, r, msg)
+/// e and s must be below q, s + q would give the same r and a second valid signature
+pub fn verify(zkp: &ZKP, y: &BigUint, msg: &[u8], signature: &Signature) -> bool {
+    if !zkp.is_group_element(y) || signature.e >= zkp.order || signature.s >= zkp.order {
+        return false;
+    }
+    let r = (ZKP::exponentiate(&zkp.alpha, &signature.s, &zkp.prime)
+        * ZKP::exponentiate(y, &signature.e, &zkp.prime))
+        % &zkp.prime;
+    challenge(zkp, y, &r, msg) == signature.e
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let x = ZKP::generate_random_number(&zkp.order);
+        let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+
+        let signature = sign(&zkp, &x, b"transfer 10 coins to bob");
+        assert_eq!(signature, sign(&zkp, &x, b"transfer 10 coins to bob"));
+        assert!(verify(&zkp, &y, b"transfer 10 coins to bob", &signature));
+        assert!(!verify(&zkp, &y, b"transfer 99 coins to bob", &signature));
+
+        let other_y = ZKP::exponentiate(&zkp.alpha, &ZKP::generate_random_number(&zkp.order), &zkp.prime);
+        assert!(!verify(&zkp, &other_y, b"transfer 10 coins to bob", &signature));
+    }
+
+    #[test]
+    fn test_nonce_differs_per_message() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let x = ZKP::generate_random_number(&zkp.order);
+
+        assert_ne!(nonce(&zkp, &x, b"first"), nonce(&zkp, &x, b"second"));
+    }
+
+    #[test]
+    fn test_s_equal_to_q_is_rejected() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let x = BigUint::from(6_u32);
+        let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+
+        // a message whose signature has s = 0, alpha^q = 1 makes s = q pass the equation as well
+        let (msg, signature) = (0u32..)
+            .map(|i| i.to_be_bytes())
+            .map(|msg| (msg, sign(&zkp, &x, &msg)))
+            .find(|(_, signature)| signature.s == BigUint::from(0_u32))
+            .unwrap();
+        assert!(verify(&zkp, &y, &msg, &signature));
+
+        let forged = Signature { s: zkp.order.clone(), ..signature.clone() };
+        assert!(!verify(&zkp, &y, &msg, &forged));
+        let forged = Signature { s: &signature.s + &zkp.order * 2u32, ..signature };
+        assert!(!verify(&zkp, &y, &msg, &forged));
+    }
+
+    #[test]
+    fn test_every_toy_signature_verifies() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let x = BigUint::from(6_u32);
+        let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+
+        // k = e x mod q with k < e x must give s = 0, not s = q
+        for i in 0u32..1000 {
+            let signature = sign(&zkp, &x, &i.to_be_bytes());
+            assert!(signature.s < zkp.order);
+            assert!(verify(&zkp, &y, &i.to_be_bytes(), &signature), "message {}", i);
+        }
+    }
+}
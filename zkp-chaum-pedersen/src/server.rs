@@ -4,24 +4,57 @@ use std::sync::Mutex;
 use num_bigint::BigUint;
 use tonic::{transport::Server, Code, Request, Response, Status};
 
-use zkp_chaum_pedersen::ZKP;
+use zkp_chaum_pedersen::{challenge_rng::{ChallengeRng, WeakLcg}, committed_challenge::ChallengeOpening, ffs::FeigeFiatShamir, gq::{self, GuillouQuisquater}, nonce_reuse::{CommitmentLog, Transcript}, params, schnorr::{self, Signature}, ZKP};
 
 pub mod zkp_auth {
     include!("./zkp_auth.rs");
 }
 
-use zkp_auth::{auth_server::{Auth, AuthServer}, AuthenticationAnswerResponse, AuthenticationAnswerRequest, AuthenticationChallengeRequest, AuthenticationChallengeResponse, RegisterRequest, RegisterResponse};
+use zkp_auth::{auth_server::{Auth, AuthServer}, AuthenticationAnswerResponse, AuthenticationAnswerRequest, AuthenticationChallengeRequest, AuthenticationChallengeResponse, ChallengeCommitmentRequest, ChallengeCommitmentResponse, ChallengeOpeningRequest, ChallengeOpeningResponse, ChallengeSpaceRequest, ChallengeSpaceResponse, FfsAnswerRequest, FfsAnswerResponse, FfsChallengeRequest, FfsChallengeResponse, FfsRegisterRequest, FfsRegisterResponse, GqAnswerRequest, GqAnswerResponse, GqChallengeRequest, GqChallengeResponse, GqRegisterRequest, GqRegisterResponse, RegisterRequest, RegisterResponse, RepeatedAnswerRequest, RepeatedAnswerResponse, RepeatedChallengeRequest, RepeatedChallengeResponse, VerifySignatureRequest, VerifySignatureResponse};
 
 const STRING_SIZE: usize = 12;
+const DEFAULT_FFS_ROUNDS: usize = 20;
+/// upper bound on the secrets a Feige-Fiat-Shamir prover may register
+const MAX_FFS_PARALLEL: usize = 64;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct AuthImpl {
+    pub zkp: ZKP,
     pub user_info: Mutex<HashMap<String, UserInfo>>,
     pub auth_id_to_username: Mutex<HashMap<String, String>>,
+    pub commitment_log: Mutex<CommitmentLog>,
+    pub challenge_rng: Mutex<ChallengeRng>,
+    /// None draws c below q
+    pub challenge_bits: Option<u64>,
+    pub repetitions: usize,
+    pub gq_user_info: Mutex<HashMap<String, GqUserInfo>>,
+    pub gq_auth_id_to_username: Mutex<HashMap<String, String>>,
+    pub ffs_rounds: usize,
+    pub ffs_user_info: Mutex<HashMap<String, FfsUserInfo>>,
+    pub ffs_auth_id_to_username: Mutex<HashMap<String, String>>,
  }
 
+impl Default for AuthImpl {
+    fn default() -> Self {
+        AuthImpl {
+            zkp: ZKP::default(),
+            user_info: Default::default(),
+            auth_id_to_username: Default::default(),
+            commitment_log: Default::default(),
+            challenge_rng: Default::default(),
+            challenge_bits: None,
+            repetitions: 1,
+            gq_user_info: Default::default(),
+            gq_auth_id_to_username: Default::default(),
+            ffs_rounds: DEFAULT_FFS_ROUNDS,
+            ffs_user_info: Default::default(),
+            ffs_auth_id_to_username: Default::default(),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
-struct UserInfo {
+pub struct UserInfo {
     // registration
     pub user_name: String,
     pub y1: BigUint,
@@ -32,7 +65,69 @@ struct UserInfo {
     // verification
     pub c: BigUint,
     pub s: BigUint,
-    pub session_id: String,
+    // committed-challenge mode, until the challenge is opened
+    pub committed_challenge: Option<ChallengeOpening>,
+    // parallel repetitions, until they are answered
+    pub transcripts: Vec<Transcript>,
+}
+
+impl AuthImpl {
+    /// min(2^challenge_bits, q)
+    fn challenge_bound(&self) -> BigUint {
+        match self.challenge_bits {
+            Some(bits) => self.zkp.challenge_bound(bits),
+            None => self.zkp.order.clone(),
+        }
+    }
+
+    /// bits of the largest possible challenge, at most the bits of q - 1
+    fn effective_challenge_bits(&self) -> u64 {
+        (self.challenge_bound() - 1u32).bits()
+    }
+
+    fn soundness_bits(&self) -> f64 {
+        ZKP::soundness_bits(&self.challenge_bound(), self.repetitions)
+    }
+
+    /// records (r1, r2) and warns if the user already sent it
+    fn record_commitment(&self, user_name: &str, r1: &BigUint, r2: &BigUint) {
+        if self.commitment_log.lock().unwrap().record(user_name, r1, r2) {
+            println!("⚠ User {} reused the commitment (r1, r2): answering two challenges with the same k leaks the secret.", user_name);
+        }
+    }
+
+    /// single-transcript logins are only sound enough without repetitions
+    fn single_transcript_error(&self) -> Option<Status> {
+        (self.repetitions > 1).then(|| {
+            Status::new(Code::FailedPrecondition, format!("The server requires {} parallel repetitions, use CreateRepeatedChallenge.", self.repetitions))
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct GqUserInfo {
+    // registration
+    pub user_name: String,
+    pub gq: GuillouQuisquater,
+    pub y: BigUint,
+    // authorization
+    pub t: BigUint,
+    // verification
+    pub c: BigUint,
+}
+
+#[derive(Debug)]
+pub struct FfsUserInfo {
+    // registration
+    pub user_name: String,
+    pub ffs: FeigeFiatShamir,
+    pub v: Vec<BigUint>,
+    // current round
+    pub x: BigUint,
+    pub a: Vec<bool>,
+    /// auth_id of the round waiting for an answer
+    pub pending: Option<String>,
+    pub rounds_passed: usize,
 }
 
 #[tonic::async_trait]
@@ -43,10 +138,12 @@ impl Auth for AuthImpl {
         let request = request.into_inner();
 
         let user_name = request.user;
-        let mut user_info = UserInfo::default();
-        user_info.user_name = user_name.clone();   
-        user_info.y1 = BigUint::from_bytes_be(&request.y1);  
-        user_info.y2 = BigUint::from_bytes_be(&request.y2);   
+        let user_info = UserInfo {
+            user_name: user_name.clone(),
+            y1: BigUint::from_bytes_be(&request.y1),
+            y2: BigUint::from_bytes_be(&request.y2),
+            ..Default::default()
+        };
 
         let user_info_hashmap = &mut self.user_info.lock().unwrap();
         user_info_hashmap.insert(user_name, user_info);
@@ -57,6 +154,10 @@ impl Auth for AuthImpl {
     async fn create_authentication_challenge(&self, request: Request<AuthenticationChallengeRequest>) -> Result<Response<AuthenticationChallengeResponse>, Status> {
         println!("Processing Challenge Request {:?}", request);
 
+        if let Some(status) = self.single_transcript_error() {
+            return Err(status);
+        }
+
         let request = request.into_inner();
 
         let user_name = request.user;
@@ -65,12 +166,16 @@ impl Auth for AuthImpl {
             .unwrap(); 
 
         if let Some(user_info) = user_info_hashmap.get_mut(&user_name) {
-            let (_, _, _, q) = ZKP::get_constants();
-            let c = ZKP::generate_random_number(&q);
+            let c = self.challenge_rng
+                .lock()
+                .unwrap()
+                .generate(&self.challenge_bound());
             let auth_id = ZKP::generate_random_string(STRING_SIZE);
 
             user_info.r1 = BigUint::from_bytes_be(&request.r1);
             user_info.r2 = BigUint::from_bytes_be(&request.r2);
+
+            self.record_commitment(&user_name, &user_info.r1, &user_info.r2);
             user_info.c = c.clone();
 
             let auth_id_to_user = &mut self.auth_id_to_username
@@ -105,10 +210,7 @@ impl Auth for AuthImpl {
             let s = BigUint::from_bytes_be(&request.s);
             user_info.s = s;
 
-            let (alpha, beta, prime, order) = ZKP::get_constants();
-            let zkp = ZKP { alpha, beta, prime, order };
-
-            let verification = zkp.verify(
+            let verification = self.zkp.verify(
                 &user_info.r1,
                 &user_info.r2,
                 &user_info.y1,
@@ -127,6 +229,408 @@ impl Auth for AuthImpl {
             Err(Status::new(Code::NotFound, format!("AuthId {} not found", auth_id)))
         }
     }
+
+    async fn verify_signature(&self, request: Request<VerifySignatureRequest>) -> Result<Response<VerifySignatureResponse>, Status> {
+        println!("Processing Signature Request {:?}", request);
+
+        let request = request.into_inner();
+
+        let user_name = request.user;
+        let user_info_hashmap = &self.user_info
+            .lock()
+            .unwrap();
+
+        if let Some(user_info) = user_info_hashmap.get(&user_name) {
+            let signature = Signature {
+                e: BigUint::from_bytes_be(&request.e),
+                s: BigUint::from_bytes_be(&request.s),
+            };
+            let valid = schnorr::verify(&self.zkp, &user_info.y1, &request.message, &signature);
+
+            Ok(Response::new(VerifySignatureResponse { valid }))
+        } else {
+            Err(Status::new(Code::NotFound, format!("User {} not found.", user_name)))
+        }
+    }
+
+    async fn get_challenge_space(&self, request: Request<ChallengeSpaceRequest>) -> Result<Response<ChallengeSpaceResponse>, Status> {
+        println!("Processing Challenge Space Request {:?}", request);
+
+        Ok(Response::new(ChallengeSpaceResponse {
+            challenge_bits: self.effective_challenge_bits() as u32,
+            repetitions: self.repetitions as u32,
+            soundness_bits: self.soundness_bits(),
+        }))
+    }
+
+    async fn create_repeated_challenge(&self, request: Request<RepeatedChallengeRequest>) -> Result<Response<RepeatedChallengeResponse>, Status> {
+        println!("Processing Repeated Challenge Request {:?}", request);
+
+        let request = request.into_inner();
+
+        let user_name = request.user;
+        if request.commitments.len() != self.repetitions {
+            return Err(Status::new(Code::InvalidArgument, format!("Expected {} commitments, got {}.", self.repetitions, request.commitments.len())));
+        }
+        let user_info_hashmap = &mut self.user_info
+            .lock()
+            .unwrap();
+
+        if let Some(user_info) = user_info_hashmap.get_mut(&user_name) {
+            let bound = self.challenge_bound();
+            let challenge_rng = &mut self.challenge_rng
+                .lock()
+                .unwrap();
+
+            user_info.transcripts = request.commitments
+                .iter()
+                .map(|commitment| Transcript {
+                    r1: BigUint::from_bytes_be(&commitment.r1),
+                    r2: BigUint::from_bytes_be(&commitment.r2),
+                    c: challenge_rng.generate(&bound),
+                    s: BigUint::from(0u32),
+                })
+                .collect();
+            for t in &user_info.transcripts {
+                self.record_commitment(&user_name, &t.r1, &t.r2);
+            }
+            let c = user_info.transcripts.iter().map(|t| t.c.to_bytes_be()).collect();
+            let auth_id = ZKP::generate_random_string(STRING_SIZE);
+
+            let auth_id_to_user = &mut self.auth_id_to_username
+                .lock()
+                .unwrap();
+            auth_id_to_user.insert(auth_id.clone(), user_name);
+
+            Ok(Response::new(RepeatedChallengeResponse { auth_id, c }))
+        } else {
+            Err(Status::new(Code::NotFound, format!("User {} not found.", user_name)))
+        }
+    }
+
+    async fn verify_repeated_authentication(&self, request: Request<RepeatedAnswerRequest>) -> Result<Response<RepeatedAnswerResponse>, Status> {
+        println!("Processing Repeated Authentication Request {:?}", request);
+
+        let request = request.into_inner();
+        let auth_id = request.auth_id;
+
+        let auth_id_to_user_hashmap = &self.auth_id_to_username
+            .lock()
+            .unwrap();
+
+        if let Some(user_name) = auth_id_to_user_hashmap.get(&auth_id) {
+            let user_info_hashmap = &mut self.user_info
+                .lock()
+                .unwrap();
+            let user_info = user_info_hashmap
+                .get_mut(user_name)
+                .expect("AuthId not found in storage");
+
+            // the challenges can only be answered once
+            let mut transcripts = std::mem::take(&mut user_info.transcripts);
+            if transcripts.is_empty() || transcripts.len() != request.s.len() {
+                return Err(Status::new(Code::FailedPrecondition, format!("AuthId {} has no pending challenges for {} answers", auth_id, request.s.len())));
+            }
+            for (transcript, s) in transcripts.iter_mut().zip(&request.s) {
+                transcript.s = BigUint::from_bytes_be(s);
+            }
+
+            if self.zkp.verify_repeated(&user_info.y1, &user_info.y2, &transcripts, &self.challenge_bound()) {
+                let soundness_bits = self.soundness_bits();
+                println!("☑ User {} answered {} challenges, soundness error 2^-{:.1}.", user_name, transcripts.len(), soundness_bits);
+                let session_id = ZKP::generate_random_string(STRING_SIZE);
+                Ok(Response::new(RepeatedAnswerResponse { session_id, soundness_bits }))
+            } else {
+                Err(Status::new(Code::PermissionDenied, format!("AuthId {} send a bad soltion to the challenge", auth_id)))
+            }
+        } else {
+            Err(Status::new(Code::NotFound, format!("AuthId {} not found", auth_id)))
+        }
+    }
+
+    async fn commit_challenge(&self, request: Request<ChallengeCommitmentRequest>) -> Result<Response<ChallengeCommitmentResponse>, Status> {
+        println!("Processing Challenge Commitment Request {:?}", request);
+
+        if let Some(status) = self.single_transcript_error() {
+            return Err(status);
+        }
+
+        let request = request.into_inner();
+
+        let user_name = request.user;
+        let user_info_hashmap = &mut self.user_info
+            .lock()
+            .unwrap();
+
+        if let Some(user_info) = user_info_hashmap.get_mut(&user_name) {
+            let c = self.challenge_rng
+                .lock()
+                .unwrap()
+                .generate(&self.challenge_bound());
+            let opening = ChallengeOpening::with_challenge(c);
+            let commitment = opening.commitment();
+            let auth_id = ZKP::generate_random_string(STRING_SIZE);
+
+            // no answer can verify against a previous login until the challenge is opened
+            user_info.r1 = BigUint::from(0u32);
+            user_info.r2 = BigUint::from(0u32);
+            user_info.committed_challenge = Some(opening);
+
+            let auth_id_to_user = &mut self.auth_id_to_username
+                .lock()
+                .unwrap();
+            auth_id_to_user.insert(auth_id.clone(), user_name);
+
+            Ok(Response::new(ChallengeCommitmentResponse { auth_id, commitment }))
+        } else {
+            Err(Status::new(Code::NotFound, format!("User {} not found.", user_name)))
+        }
+    }
+
+    async fn open_challenge(&self, request: Request<ChallengeOpeningRequest>) -> Result<Response<ChallengeOpeningResponse>, Status> {
+        println!("Processing Challenge Opening Request {:?}", request);
+
+        let request = request.into_inner();
+        let auth_id = request.auth_id;
+
+        let auth_id_to_user_hashmap = &self.auth_id_to_username
+            .lock()
+            .unwrap();
+
+        if let Some(user_name) = auth_id_to_user_hashmap.get(&auth_id) {
+            let user_info_hashmap = &mut self.user_info
+                .lock()
+                .unwrap();
+            let user_info = user_info_hashmap
+                .get_mut(user_name)
+                .expect("AuthId not found in storage");
+
+            // opening twice would let the prover pick (r1, r2) after seeing c
+            let opening = user_info.committed_challenge
+                .take()
+                .ok_or_else(|| Status::new(Code::FailedPrecondition, format!("AuthId {} has no unopened challenge", auth_id)))?;
+
+            user_info.r1 = BigUint::from_bytes_be(&request.r1);
+            user_info.r2 = BigUint::from_bytes_be(&request.r2);
+            user_info.c = opening.c.clone();
+
+            self.record_commitment(user_name, &user_info.r1, &user_info.r2);
+
+            Ok(Response::new(ChallengeOpeningResponse { c: opening.c.to_bytes_be(), nonce: opening.nonce }))
+        } else {
+            Err(Status::new(Code::NotFound, format!("AuthId {} not found", auth_id)))
+        }
+    }
+
+    async fn register_gq(&self, request: Request<GqRegisterRequest>) -> Result<Response<GqRegisterResponse>, Status> {
+        println!("Processing GQ Register Request {:?}", request);
+
+        let request = request.into_inner();
+
+        let user_name = request.user;
+        let gq = GuillouQuisquater {
+            modulus: BigUint::from_bytes_be(&request.modulus),
+            exponent: BigUint::from_bytes_be(&request.exponent),
+        };
+        let y = BigUint::from_bytes_be(&request.y);
+        if !gq.is_valid() || !gq.is_unit(&y) {
+            return Err(Status::new(Code::InvalidArgument, format!("User {} sent an invalid modulus, exponent or public key.", user_name)));
+        }
+        // the prover picks e, and a single round only catches a cheater with probability 1 - 1/e
+        if gq.exponent < BigUint::from(gq::MIN_EXPONENT) {
+            return Err(Status::new(Code::InvalidArgument, format!("User {} sent an exponent below {}.", user_name, gq::MIN_EXPONENT)));
+        }
+
+        let user_info = GqUserInfo {
+            user_name: user_name.clone(),
+            gq,
+            y,
+            t: BigUint::from(0u32),
+            c: BigUint::from(0u32),
+        };
+
+        let user_info_hashmap = &mut self.gq_user_info.lock().unwrap();
+        user_info_hashmap.insert(user_name, user_info);
+
+        Ok(Response::new(GqRegisterResponse {  }))
+    }
+
+    async fn create_gq_challenge(&self, request: Request<GqChallengeRequest>) -> Result<Response<GqChallengeResponse>, Status> {
+        println!("Processing GQ Challenge Request {:?}", request);
+
+        let request = request.into_inner();
+
+        let user_name = request.user;
+        let user_info_hashmap = &mut self.gq_user_info
+            .lock()
+            .unwrap();
+
+        if let Some(user_info) = user_info_hashmap.get_mut(&user_name) {
+            let c = self.challenge_rng
+                .lock()
+                .unwrap()
+                .generate(&user_info.gq.exponent);
+            let auth_id = ZKP::generate_random_string(STRING_SIZE);
+
+            user_info.t = BigUint::from_bytes_be(&request.t);
+            user_info.c = c.clone();
+
+            let auth_id_to_user = &mut self.gq_auth_id_to_username
+                .lock()
+                .unwrap();
+            auth_id_to_user.insert(auth_id.clone(), user_name);
+
+            Ok(Response::new(GqChallengeResponse { auth_id, c: c.to_bytes_be() }))
+        } else {
+            Err(Status::new(Code::NotFound, format!("User {} not found.", user_name)))
+        }
+    }
+
+    async fn verify_gq_authentication(&self, request: Request<GqAnswerRequest>) -> Result<Response<GqAnswerResponse>, Status> {
+        println!("Processing GQ Authentication Request {:?}", request);
+
+        let request = request.into_inner();
+        let auth_id = request.auth_id;
+
+        let auth_id_to_user_hashmap = &mut self.gq_auth_id_to_username
+            .lock()
+            .unwrap();
+
+        // every challenge can be answered once, a replayed answer must not open a new session
+        if let Some(user_name) = auth_id_to_user_hashmap.remove(&auth_id) {
+            let user_info_hashmap = &self.gq_user_info
+                .lock()
+                .unwrap();
+            let user_info = user_info_hashmap
+                .get(&user_name)
+                .expect("AuthId not found in storage");
+
+            let s = BigUint::from_bytes_be(&request.s);
+            if user_info.gq.verify(&user_info.t, &user_info.y, &user_info.c, &s) {
+                let session_id = ZKP::generate_random_string(STRING_SIZE);
+                Ok(Response::new(GqAnswerResponse { session_id }))
+            } else {
+                Err(Status::new(Code::PermissionDenied, format!("AuthId {} send a bad soltion to the challenge", auth_id)))
+            }
+        } else {
+            Err(Status::new(Code::NotFound, format!("AuthId {} not found", auth_id)))
+        }
+    }
+
+    async fn register_ffs(&self, request: Request<FfsRegisterRequest>) -> Result<Response<FfsRegisterResponse>, Status> {
+        println!("Processing FFS Register Request {:?}", request);
+
+        let request = request.into_inner();
+
+        let user_name = request.user;
+        let ffs = FeigeFiatShamir {
+            modulus: BigUint::from_bytes_be(&request.modulus),
+            parallel: request.v.len(),
+            rounds: self.ffs_rounds,
+        };
+        let v: Vec<BigUint> = request.v.iter().map(|v| BigUint::from_bytes_be(v)).collect();
+        if !ffs.modulus.bit(0) || !(1..=MAX_FFS_PARALLEL).contains(&ffs.parallel) || !v.iter().all(|v| ffs.is_unit(v)) {
+            return Err(Status::new(Code::InvalidArgument, format!("User {} sent an invalid modulus or public keys.", user_name)));
+        }
+
+        let user_info = FfsUserInfo {
+            user_name: user_name.clone(),
+            ffs,
+            v,
+            x: BigUint::from(0u32),
+            a: Vec::new(),
+            pending: None,
+            rounds_passed: 0,
+        };
+
+        let user_info_hashmap = &mut self.ffs_user_info.lock().unwrap();
+        user_info_hashmap.insert(user_name, user_info);
+
+        Ok(Response::new(FfsRegisterResponse { rounds: self.ffs_rounds as u32 }))
+    }
+
+    async fn create_ffs_challenge(&self, request: Request<FfsChallengeRequest>) -> Result<Response<FfsChallengeResponse>, Status> {
+        println!("Processing FFS Challenge Request {:?}", request);
+
+        let request = request.into_inner();
+
+        let user_name = request.user;
+        let user_info_hashmap = &mut self.ffs_user_info
+            .lock()
+            .unwrap();
+
+        if let Some(user_info) = user_info_hashmap.get_mut(&user_name) {
+            // abandoning a round counts as failing it, otherwise a cheater could
+            // skip every challenge it did not guess
+            if user_info.pending.is_some() {
+                user_info.rounds_passed = 0;
+            }
+
+            let bound = BigUint::from(1u32) << user_info.ffs.parallel;
+            let c = self.challenge_rng
+                .lock()
+                .unwrap()
+                .generate(&bound);
+            let a: Vec<bool> = (0..user_info.ffs.parallel as u64).map(|i| c.bit(i)).collect();
+            let auth_id = ZKP::generate_random_string(STRING_SIZE);
+
+            user_info.x = BigUint::from_bytes_be(&request.x);
+            user_info.a = a.clone();
+            user_info.pending = Some(auth_id.clone());
+
+            let auth_id_to_user = &mut self.ffs_auth_id_to_username
+                .lock()
+                .unwrap();
+            auth_id_to_user.insert(auth_id.clone(), user_name);
+
+            Ok(Response::new(FfsChallengeResponse { auth_id, a }))
+        } else {
+            Err(Status::new(Code::NotFound, format!("User {} not found.", user_name)))
+        }
+    }
+
+    async fn verify_ffs_authentication(&self, request: Request<FfsAnswerRequest>) -> Result<Response<FfsAnswerResponse>, Status> {
+        println!("Processing FFS Authentication Request {:?}", request);
+
+        let request = request.into_inner();
+        let auth_id = request.auth_id;
+
+        let auth_id_to_user_hashmap = &mut self.ffs_auth_id_to_username
+            .lock()
+            .unwrap();
+
+        if let Some(user_name) = auth_id_to_user_hashmap.remove(&auth_id) {
+            let user_info_hashmap = &mut self.ffs_user_info
+                .lock()
+                .unwrap();
+            let user_info = user_info_hashmap
+                .get_mut(&user_name)
+                .expect("AuthId not found in storage");
+            if user_info.pending.as_ref() != Some(&auth_id) {
+                return Err(Status::new(Code::FailedPrecondition, format!("AuthId {} belongs to an abandoned round", auth_id)));
+            }
+            user_info.pending = None;
+
+            let y = BigUint::from_bytes_be(&request.y);
+            if !user_info.ffs.verify(&user_info.x, &user_info.v, &user_info.a, &y) {
+                user_info.rounds_passed = 0;
+                return Err(Status::new(Code::PermissionDenied, format!("AuthId {} send a bad soltion to the challenge", auth_id)));
+            }
+
+            user_info.rounds_passed += 1;
+            let rounds_left = user_info.ffs.rounds - user_info.rounds_passed;
+            if rounds_left > 0 {
+                return Ok(Response::new(FfsAnswerResponse { rounds_left: rounds_left as u32, session_id: String::new() }));
+            }
+
+            user_info.rounds_passed = 0;
+            println!("☑ User {} passed {} rounds, soundness error {:e}.", user_name, user_info.ffs.rounds, user_info.ffs.soundness_error());
+            let session_id = ZKP::generate_random_string(STRING_SIZE);
+            Ok(Response::new(FfsAnswerResponse { rounds_left: 0, session_id }))
+        } else {
+            Err(Status::new(Code::NotFound, format!("AuthId {} not found", auth_id)))
+        }
+    }
 }
 
 #[tokio::main]
@@ -134,7 +638,31 @@ async fn main() {
     let addr = "127.0.0.1:50051".to_string();
     println!("☑ Running the server in {}.", addr);
 
-    let auth_impl = AuthImpl::default();
+    let zkp = params::from_args_or_default().expect("could not load the group parameters");
+    let mut auth_impl = AuthImpl { zkp, ..Default::default() };
+    if let Some(rounds) = params::arg_value("--ffs-rounds") {
+        auth_impl.ffs_rounds = rounds.parse().ok().filter(|rounds| *rounds > 0).expect("--ffs-rounds expects a positive number");
+    }
+    if let Some(bits) = params::arg_value("--challenge-bits") {
+        auth_impl.challenge_bits = Some(bits.parse().ok().filter(|bits| *bits > 0).expect("--challenge-bits expects a positive number"));
+    }
+    if let Some(repetitions) = params::arg_value("--repetitions") {
+        auth_impl.repetitions = repetitions.parse().ok().filter(|repetitions| *repetitions > 0).expect("--repetitions expects a positive number");
+    }
+    println!(
+        "☑ Challenges have {} bits, {} per login: soundness error 2^-{:.1}.",
+        auth_impl.effective_challenge_bits(),
+        auth_impl.repetitions,
+        auth_impl.soundness_bits(),
+    );
+    if std::env::args().any(|arg| arg == "--weak-challenge-rng") {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("system time is before the unix epoch")
+            .as_secs();
+        auth_impl.challenge_rng = Mutex::new(ChallengeRng::Weak(WeakLcg::new(seed)));
+        println!("⚠ Demo mode: challenges come from a predictable LCG, do not use in production.");
+    }
 
     Server::builder()
         .add_service(AuthServer::new(auth_impl))
@@ -0,0 +1,126 @@
+use num_bigint::BigUint;
+
+use crate::ZKP;
+
+/// f(z) = a_0 + a_1 z + ... + a_{t-1} z^{t-1} mod q with the secret as a_0
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    pub coefficients: Vec<BigUint>,
+}
+
+/// (i, f(i)) for i = 1..n
+#[derive(Debug, Clone, PartialEq)]
+pub struct Share {
+    pub index: u32,
+    pub value: BigUint,
+}
+
+impl Polynomial {
+    /// random polynomial of degree threshold - 1 with f(0) = secret
+    pub fn random(order: &BigUint, secret: &BigUint, threshold: usize) -> Self {
+        assert!(threshold > 0, "threshold must be at least 1");
+        let mut coefficients = vec![secret % order];
+        for _ in 1..threshold {
+            coefficients.push(ZKP::generate_random_number(order));
+        }
+        Polynomial { coefficients }
+    }
+
+    /// f(z) mod q by Horner's rule
+    pub fn evaluate(&self, z: &BigUint, order: &BigUint) -> BigUint {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u32), |acc, coefficient| (acc * z + coefficient) % order)
+    }
+
+    /// shares for parties 1..=n
+    /// n must be below q, the share of a multiple of q would be f(0) itself
+    pub fn shares(&self, n: u32, order: &BigUint) -> Vec<Share> {
+        assert!(BigUint::from(n) < *order, "the number of shares must be below q");
+        (1..=n)
+            .map(|index| Share { index, value: self.evaluate(&BigUint::from(index), order) })
+            .collect()
+    }
+}
+
+/// an index that is not 0 mod q, at 0 the polynomial reveals the secret
+pub fn is_valid_index(order: &BigUint, index: u32) -> bool {
+    BigUint::from(index) % order != BigUint::from(0u32)
+}
+
+/// splits the secret into n < q shares, any threshold of which reconstruct it
+pub fn split(order: &BigUint, secret: &BigUint, threshold: usize, n: u32) -> Vec<Share> {
+    assert!(threshold <= n as usize, "threshold must not exceed the number of shares");
+    Polynomial::random(order, secret, threshold).shares(n, order)
+}
+
+/// lambda_i = prod_{j != i} j / (j - i) mod q, the weight of f(i) in f(0)
+/// returns None if the index is 0 mod q, repeated or missing from `indices`
+pub fn lagrange_coefficient(order: &BigUint, index: u32, indices: &[u32]) -> Option<BigUint> {
+    if !is_valid_index(order, index) || indices.iter().filter(|&&other| other == index).count() != 1 {
+        return None;
+    }
+    let i = BigUint::from(index);
+    let mut numerator = BigUint::from(1u32);
+    let mut denominator = BigUint::from(1u32);
+    for &other in indices.iter().filter(|&&other| other != index) {
+        let j = BigUint::from(other);
+        numerator = (numerator * &j) % order;
+        denominator = (denominator * ((&j + order - &i % order) % order)) % order;
+    }
+    Some((numerator * denominator.modinv(order)?) % order)
+}
+
+/// f(0) = sum lambda_i f(i) mod q
+/// returns None on repeated indices or an index that is 0 mod q
+pub fn reconstruct(order: &BigUint, shares: &[Share]) -> Option<BigUint> {
+    let indices: Vec<u32> = shares.iter().map(|share| share.index).collect();
+    let mut secret = BigUint::from(0u32);
+    for share in shares {
+        let lambda = lagrange_coefficient(order, share.index, &indices)?;
+        secret = (secret + lambda * &share.value) % order;
+    }
+    Some(secret)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_and_reconstruct_toy_example() {
+        let order = BigUint::from(11_u32);
+        let secret = BigUint::from(6_u32);
+        let shares = split(&order, &secret, 3, 5);
+
+        assert_eq!(reconstruct(&order, &shares[..3]), Some(secret.clone()));
+        assert_eq!(reconstruct(&order, &shares[2..]), Some(secret.clone()));
+        assert_eq!(reconstruct(&order, &shares), Some(secret));
+
+        let repeated = [shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert_eq!(reconstruct(&order, &repeated), None);
+
+        // index 11 = 0 mod 11 would be f(0) itself
+        let at_q = Share { index: 11, value: BigUint::from(6_u32) };
+        assert_eq!(reconstruct(&order, &[shares[0].clone(), shares[1].clone(), at_q]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "below q")]
+    fn test_split_rejects_n_of_at_least_q() {
+        split(&BigUint::from(11_u32), &BigUint::from(7_u32), 3, 12);
+    }
+
+    #[test]
+    fn test_polynomial_evaluate() {
+        let order = BigUint::from(11_u32);
+        // f(z) = 6 + 2z + 5z^2
+        let polynomial = Polynomial {
+            coefficients: vec![BigUint::from(6_u32), BigUint::from(2_u32), BigUint::from(5_u32)],
+        };
+        assert_eq!(polynomial.evaluate(&BigUint::from(0_u32), &order), BigUint::from(6_u32));
+        assert_eq!(polynomial.evaluate(&BigUint::from(1_u32), &order), BigUint::from(2_u32));
+        assert_eq!(polynomial.evaluate(&BigUint::from(3_u32), &order), BigUint::from(2_u32));
+    }
+}
@@ -0,0 +1,167 @@
+use num_bigint::BigUint;
+
+use crate::{
+    dleq::{self, DleqProof},
+    elgamal::{self, Ciphertext},
+    shamir,
+    ZKP,
+};
+
+/// secret share x_i of trustee i
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyShare {
+    pub index: u32,
+    pub x: BigUint,
+}
+
+/// public part of a t-of-n key: y = alpha^x and y_i = alpha^x_i for every trustee
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdKey {
+    pub y: BigUint,
+    pub threshold: usize,
+    pub public_shares: Vec<(u32, BigUint)>,
+}
+
+/// d_i = c1^x_i with the proof that log_alpha(y_i) == log_c1(d_i)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialDecryption {
+    pub index: u32,
+    pub d: BigUint,
+    pub proof: DleqProof,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CombineError {
+    NotEnoughShares,
+    UnknownTrustee(u32),
+    DuplicateShare(u32),
+    InvalidShare(u32),
+}
+
+impl ThresholdKey {
+    pub fn public_share(&self, index: u32) -> Option<&BigUint> {
+        self.public_shares
+            .iter()
+            .find(|(share_index, _)| *share_index == index)
+            .map(|(_, y_i)| y_i)
+    }
+}
+
+/// trusted dealer: draws x, shares it t-of-n and forgets it
+pub fn deal(zkp: &ZKP, threshold: usize, n: u32) -> (ThresholdKey, Vec<KeyShare>) {
+    let x = ZKP::generate_random_number(&zkp.order);
+    let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+    let shares: Vec<KeyShare> = shamir::split(&zkp.order, &x, threshold, n)
+        .into_iter()
+        .map(|share| KeyShare { index: share.index, x: share.value })
+        .collect();
+    let public_shares = shares
+        .iter()
+        .map(|share| (share.index, ZKP::exponentiate(&zkp.alpha, &share.x, &zkp.prime)))
+        .collect();
+    (ThresholdKey { y, threshold, public_shares }, shares)
+}
+
+/// trustee side: d_i = c1^x_i and its DLEQ proof
+pub fn partial_decrypt(zkp: &ZKP, share: &KeyShare, ciphertext: &Ciphertext) -> PartialDecryption {
+    let y_i = ZKP::exponentiate(&zkp.alpha, &share.x, &zkp.prime);
+    let d = elgamal::decryption_factor(zkp, &share.x, ciphertext);
+    let group = dleq::with_second_base(zkp, ciphertext.c1.clone());
+    let proof = dleq::prove(&group, &share.x, &y_i, &d);
+    PartialDecryption { index: share.index, d, proof }
+}
+
+/// checks d_i against the trustee's public share y_i
+pub fn verify_partial(zkp: &ZKP, key: &ThresholdKey, ciphertext: &Ciphertext, partial: &PartialDecryption) -> bool {
+    let Some(y_i) = key.public_share(partial.index) else {
+        return false;
+    };
+    if !zkp.is_group_element(&ciphertext.c1) || !zkp.is_group_element(&partial.d) {
+        return false;
+    }
+    let group = dleq::with_second_base(zkp, ciphertext.c1.clone());
+    dleq::verify(&group, y_i, &partial.d, &partial.proof)
+}
+
+/// verifies every partial decryption, then d = prod d_i^lambda_i = c1^x and m = c2 / d
+pub fn combine(
+    zkp: &ZKP,
+    key: &ThresholdKey,
+    ciphertext: &Ciphertext,
+    partials: &[PartialDecryption],
+) -> Result<BigUint, CombineError> {
+    let mut indices = Vec::with_capacity(partials.len());
+    for partial in partials {
+        if key.public_share(partial.index).is_none() {
+            return Err(CombineError::UnknownTrustee(partial.index));
+        }
+        if indices.contains(&partial.index) {
+            return Err(CombineError::DuplicateShare(partial.index));
+        }
+        if !verify_partial(zkp, key, ciphertext, partial) {
+            return Err(CombineError::InvalidShare(partial.index));
+        }
+        indices.push(partial.index);
+    }
+    if indices.len() < key.threshold {
+        return Err(CombineError::NotEnoughShares);
+    }
+
+    let indices = &indices[..key.threshold];
+    let mut d = BigUint::from(1u32);
+    for partial in &partials[..key.threshold] {
+        let lambda = shamir::lagrange_coefficient(&zkp.order, partial.index, indices)
+            .expect("indices are distinct and non-zero");
+        d = (d * ZKP::exponentiate(&partial.d, &lambda, &zkp.prime)) % &zkp.prime;
+    }
+    Ok(elgamal::unmask(zkp, ciphertext, &d).expect("d is a group element"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_threshold_decryption_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let (key, shares) = deal(&zkp, 3, 5);
+        let m = ZKP::exponentiate(&zkp.alpha, &BigUint::from(1234_u32), &zkp.prime);
+        let ciphertext = elgamal::encrypt(&zkp, &key.y, &m);
+
+        let partials: Vec<PartialDecryption> = [4, 1, 2]
+            .iter()
+            .map(|&i| partial_decrypt(&zkp, &shares[i], &ciphertext))
+            .collect();
+        assert!(partials.iter().all(|partial| verify_partial(&zkp, &key, &ciphertext, partial)));
+        assert_eq!(combine(&zkp, &key, &ciphertext, &partials), Ok(m.clone()));
+        assert_eq!(combine(&zkp, &key, &ciphertext, &partials[..2]), Err(CombineError::NotEnoughShares));
+
+        let mut tampered = partials.clone();
+        tampered[1].d = (&tampered[1].d * &zkp.alpha) % &zkp.prime;
+        assert_eq!(combine(&zkp, &key, &ciphertext, &tampered), Err(CombineError::InvalidShare(2)));
+    }
+
+    #[test]
+    fn test_combine_rejects_bad_shares() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let (key, shares) = deal(&zkp, 2, 3);
+        let m = BigUint::from(13_u32);
+        let ciphertext = elgamal::encrypt(&zkp, &key.y, &m);
+
+        let first = partial_decrypt(&zkp, &shares[0], &ciphertext);
+        let second = partial_decrypt(&zkp, &shares[1], &ciphertext);
+        assert_eq!(combine(&zkp, &key, &ciphertext, &[first.clone(), second.clone()]), Ok(m));
+
+        let duplicate = [first.clone(), first.clone()];
+        assert_eq!(combine(&zkp, &key, &ciphertext, &duplicate), Err(CombineError::DuplicateShare(1)));
+
+        let unknown = PartialDecryption { index: 9, ..second.clone() };
+        assert_eq!(combine(&zkp, &key, &ciphertext, &[first, unknown]), Err(CombineError::UnknownTrustee(9)));
+    }
+}
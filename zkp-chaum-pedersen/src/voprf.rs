@@ -0,0 +1,152 @@
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::{dleq::{self, DleqProof}, hash, ZKP};
+
+const VOPRF_HASH_TO_GROUP_DST: &[u8] = b"zkp-chaum-pedersen/voprf/hash-to-group";
+const VOPRF_FINALIZE_DST: &[u8] = b"Finalize";
+
+/// inputs are hashed with a two-byte length prefix
+pub const MAX_INPUT_LEN: usize = u16::MAX as usize;
+
+/// client state between Blind and Finalize
+/// element = H(input)^blind is sent to the server, blind stays with the client
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlindedInput {
+    pub blind: BigUint,
+    pub element: BigUint,
+}
+
+/// server key pair (k, pk = alpha^k)
+pub fn keygen(zkp: &ZKP) -> (BigUint, BigUint) {
+    let k = random_nonzero_scalar(zkp);
+    let pk = ZKP::exponentiate(&zkp.alpha, &k, &zkp.prime);
+    (k, pk)
+}
+
+fn random_nonzero_scalar(zkp: &ZKP) -> BigUint {
+    let one = BigUint::from(1u32);
+    ZKP::generate_random_number(&(&zkp.order - &one)) + one
+}
+
+/// output = SHA-256(len(input) || input || len(N) || N || "Finalize")
+/// returns None if a length does not fit its two bytes
+fn finalize_hash(input: &[u8], unblinded: &BigUint) -> Option<Vec<u8>> {
+    let unblinded = unblinded.to_bytes_be();
+    let mut hasher = Sha256::new();
+    hasher.update(u16::try_from(input.len()).ok()?.to_be_bytes());
+    hasher.update(input);
+    hasher.update(u16::try_from(unblinded.len()).ok()?.to_be_bytes());
+    hasher.update(&unblinded);
+    hasher.update(VOPRF_FINALIZE_DST);
+    Some(hasher.finalize().to_vec())
+}
+
+/// Blind: element = H(input)^blind with a fresh random blind
+/// returns None for inputs longer than MAX_INPUT_LEN, as RFC 9497 does
+pub fn blind(zkp: &ZKP, input: &[u8]) -> Option<BlindedInput> {
+    if input.len() > MAX_INPUT_LEN {
+        return None;
+    }
+    let point = hash::hash_to_group(zkp, VOPRF_HASH_TO_GROUP_DST, &[input]);
+    let blind = random_nonzero_scalar(zkp);
+    let element = ZKP::exponentiate(&point, &blind, &zkp.prime);
+    Some(BlindedInput { blind, element })
+}
+
+/// BlindEvaluate: evaluated = element^k and a DLEQ proof that log_alpha(pk) == log_element(evaluated)
+/// returns None if the blinded element is not in the group
+pub fn blind_evaluate(zkp: &ZKP, k: &BigUint, element: &BigUint) -> Option<(BigUint, DleqProof)> {
+    if !zkp.is_group_element(element) {
+        return None;
+    }
+    let pk = ZKP::exponentiate(&zkp.alpha, k, &zkp.prime);
+    let evaluated = ZKP::exponentiate(element, k, &zkp.prime);
+    let group = dleq::with_second_base(zkp, element.clone());
+    let proof = dleq::prove(&group, k, &pk, &evaluated);
+    Some((evaluated, proof))
+}
+
+/// Finalize: checks the proof against the server's committed pk and unblinds
+/// returns None if the server did not use the key behind pk
+pub fn finalize(
+    zkp: &ZKP,
+    pk: &BigUint,
+    input: &[u8],
+    blinded: &BlindedInput,
+    evaluated: &BigUint,
+    proof: &DleqProof,
+) -> Option<Vec<u8>> {
+    if !zkp.is_group_element(evaluated) {
+        return None;
+    }
+    let group = dleq::with_second_base(zkp, blinded.element.clone());
+    if !dleq::verify(&group, pk, evaluated, proof) {
+        return None;
+    }
+    let inverse_blind = blinded.blind.modinv(&zkp.order)?;
+    let unblinded = ZKP::exponentiate(evaluated, &inverse_blind, &zkp.prime);
+    finalize_hash(input, &unblinded)
+}
+
+/// Evaluate: the PRF computed directly by the key holder, equal to the client's Finalize output
+/// returns None for inputs longer than MAX_INPUT_LEN
+pub fn evaluate(zkp: &ZKP, k: &BigUint, input: &[u8]) -> Option<Vec<u8>> {
+    if input.len() > MAX_INPUT_LEN {
+        return None;
+    }
+    let point = hash::hash_to_group(zkp, VOPRF_HASH_TO_GROUP_DST, &[input]);
+    let unblinded = ZKP::exponentiate(&point, k, &zkp.prime);
+    finalize_hash(input, &unblinded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_voprf_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let (k, pk) = keygen(&zkp);
+
+        let input = b"correct horse battery staple";
+        let blinded = blind(&zkp, input).unwrap();
+        let (evaluated, proof) = blind_evaluate(&zkp, &k, &blinded.element).unwrap();
+        let output = finalize(&zkp, &pk, input, &blinded, &evaluated, &proof).unwrap();
+
+        assert_eq!(Some(output.clone()), evaluate(&zkp, &k, input));
+        assert_ne!(Some(output), evaluate(&zkp, &k, b"another input"));
+    }
+
+    #[test]
+    fn test_voprf_rejects_other_key() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let (_, pk) = keygen(&zkp);
+        let (other_k, _) = keygen(&zkp);
+
+        let blinded = blind(&zkp, b"input").unwrap();
+        let (evaluated, proof) = blind_evaluate(&zkp, &other_k, &blinded.element).unwrap();
+
+        assert_eq!(finalize(&zkp, &pk, b"input", &blinded, &evaluated, &proof), None);
+        assert_eq!(blind_evaluate(&zkp, &other_k, &(&zkp.prime - 1u32)), None);
+    }
+
+    #[test]
+    fn test_voprf_rejects_oversized_input() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let (k, _) = keygen(&zkp);
+
+        let longest = vec![7u8; MAX_INPUT_LEN];
+        assert!(blind(&zkp, &longest).is_some());
+        assert!(evaluate(&zkp, &k, &longest).is_some());
+
+        // 65536 bytes would wrap to a zero length prefix
+        let oversized = vec![7u8; MAX_INPUT_LEN + 1];
+        assert_eq!(blind(&zkp, &oversized), None);
+        assert_eq!(evaluate(&zkp, &k, &oversized), None);
+        assert_eq!(finalize_hash(&oversized, &zkp.alpha), None);
+    }
+}
@@ -0,0 +1,87 @@
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+
+use crate::{dleq::{self, DleqProof}, hash, ZKP};
+
+const VRF_HASH_TO_GROUP_DST: &[u8] = b"zkp-chaum-pedersen/vrf/hash-to-group";
+const VRF_OUTPUT_DST: &[u8] = b"zkp-chaum-pedersen/vrf/output";
+
+/// gamma = H(msg)^x together with the proof that log_H(msg)(gamma) == log_alpha(y)
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrfProof {
+    pub gamma: BigUint,
+    pub dleq: DleqProof,
+}
+
+/// group whose bases are the generator alpha and the hashed message H(msg)
+fn message_group(zkp: &ZKP, msg: &[u8]) -> ZKP {
+    dleq::with_second_base(zkp, hash::hash_to_group(zkp, VRF_HASH_TO_GROUP_DST, &[msg]))
+}
+
+/// output = SHA-256(dst || gamma)
+fn output_from_gamma(gamma: &BigUint) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(VRF_OUTPUT_DST);
+    hasher.update(gamma.to_bytes_be());
+    hasher.finalize().to_vec()
+}
+
+/// evaluates the VRF with the secret x of the public key y = alpha^x
+/// returns (output, proof)
+pub fn prove(zkp: &ZKP, x: &BigUint, msg: &[u8]) -> (Vec<u8>, VrfProof) {
+    let group = message_group(zkp, msg);
+    let y = ZKP::exponentiate(&group.alpha, x, &group.prime);
+    let gamma = ZKP::exponentiate(&group.beta, x, &group.prime);
+    let dleq = dleq::prove(&group, x, &y, &gamma);
+    (output_from_gamma(&gamma), VrfProof { gamma, dleq })
+}
+
+/// checks that `output` is the VRF of `msg` under the public key y = alpha^x
+pub fn verify(zkp: &ZKP, y: &BigUint, msg: &[u8], output: &[u8], proof: &VrfProof) -> bool {
+    if !zkp.is_group_element(y) || !zkp.is_group_element(&proof.gamma) {
+        return false;
+    }
+    let group = message_group(zkp, msg);
+    dleq::verify(&group, y, &proof.gamma, &proof.dleq) && output_from_gamma(&proof.gamma) == output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_vrf_toy_example() {
+        let zkp = ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        };
+        let x = BigUint::from(6_u32);
+        let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+
+        let (output, proof) = prove(&zkp, &x, b"round 1");
+        assert!(verify(&zkp, &y, b"round 1", &output, &proof));
+    }
+
+    #[test]
+    fn test_vrf_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+
+        let x = ZKP::generate_random_number(&zkp.order);
+        let y = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+
+        let (output, proof) = prove(&zkp, &x, b"leader election epoch 7");
+        let (output_again, _) = prove(&zkp, &x, b"leader election epoch 7");
+        assert_eq!(output, output_again);
+        assert!(verify(&zkp, &y, b"leader election epoch 7", &output, &proof));
+
+        let other_y = ZKP::exponentiate(&zkp.alpha, &ZKP::generate_random_number(&zkp.order), &zkp.prime);
+        assert!(!verify(&zkp, &other_y, b"leader election epoch 7", &output, &proof));
+
+        let mut forged_output = output.clone();
+        forged_output[0] ^= 1;
+        assert!(!verify(&zkp, &y, b"leader election epoch 7", &forged_output, &proof));
+    }
+}
@@ -0,0 +1,137 @@
+use num_bigint::BigUint;
+
+use crate::{pedersen, shamir::{self, Polynomial, Share}, ZKP};
+
+/// Pedersen VSS share: (i, f(i), g(i)) where g is the blinding polynomial
+#[derive(Debug, Clone, PartialEq)]
+pub struct PedersenShare {
+    pub index: u32,
+    pub value: BigUint,
+    pub blinding: BigUint,
+}
+
+impl PedersenShare {
+    pub fn to_share(&self) -> Share {
+        Share { index: self.index, value: self.value.clone() }
+    }
+}
+
+/// prod_j C_j^(i^j) mod p, the commitment to f(i) implied by the coefficient commitments
+pub fn commitment_at(zkp: &ZKP, commitments: &[BigUint], index: u32) -> BigUint {
+    let i = BigUint::from(index);
+    let mut power = BigUint::from(1u32);
+    let mut result = BigUint::from(1u32);
+    for commitment in commitments {
+        result = (result * ZKP::exponentiate(commitment, &power, &zkp.prime)) % &zkp.prime;
+        power = (power * &i) % &zkp.order;
+    }
+    result
+}
+
+/// C_j = alpha^a_j for every coefficient of the sharing polynomial
+pub fn feldman_commitments(zkp: &ZKP, polynomial: &Polynomial) -> Vec<BigUint> {
+    polynomial.coefficients
+        .iter()
+        .map(|coefficient| ZKP::exponentiate(&zkp.alpha, coefficient, &zkp.prime))
+        .collect()
+}
+
+/// shares the secret t-of-n and publishes C_j = alpha^a_j
+/// C_0 = alpha^secret is public, so the secret is only computationally hidden
+/// returns (commitments, shares)
+pub fn feldman_deal(zkp: &ZKP, secret: &BigUint, threshold: usize, n: u32) -> (Vec<BigUint>, Vec<Share>) {
+    let polynomial = Polynomial::random(&zkp.order, secret, threshold);
+    (feldman_commitments(zkp, &polynomial), polynomial.shares(n, &zkp.order))
+}
+
+/// alpha^f(i) == prod_j C_j^(i^j)
+pub fn feldman_verify(zkp: &ZKP, commitments: &[BigUint], share: &Share) -> bool {
+    shamir::is_valid_index(&zkp.order, share.index)
+        && ZKP::exponentiate(&zkp.alpha, &share.value, &zkp.prime) == commitment_at(zkp, commitments, share.index)
+}
+
+/// shares the secret with f and a random blinding polynomial g and publishes C_j = alpha^a_j h^b_j
+/// the commitments are perfectly hiding, h comes from `pedersen::generator`
+/// returns (commitments, shares)
+pub fn pedersen_deal(
+    zkp: &ZKP,
+    h: &BigUint,
+    secret: &BigUint,
+    threshold: usize,
+    n: u32,
+) -> (Vec<BigUint>, Vec<PedersenShare>) {
+    let polynomial = Polynomial::random(&zkp.order, secret, threshold);
+    let blinding = Polynomial::random(&zkp.order, &ZKP::generate_random_number(&zkp.order), threshold);
+
+    let commitments = polynomial.coefficients
+        .iter()
+        .zip(&blinding.coefficients)
+        .map(|(a, b)| pedersen::commit(zkp, h, a, b))
+        .collect();
+    let shares = polynomial.shares(n, &zkp.order)
+        .into_iter()
+        .zip(blinding.shares(n, &zkp.order))
+        .map(|(share, blind)| PedersenShare { index: share.index, value: share.value, blinding: blind.value })
+        .collect();
+    (commitments, shares)
+}
+
+/// alpha^f(i) h^g(i) == prod_j C_j^(i^j)
+pub fn pedersen_verify(zkp: &ZKP, h: &BigUint, commitments: &[BigUint], share: &PedersenShare) -> bool {
+    shamir::is_valid_index(&zkp.order, share.index)
+        && pedersen::commit(zkp, h, &share.value, &share.blinding) == commitment_at(zkp, commitments, share.index)
+}
+
+/// Lagrange interpolation of f(0) mod q from at least threshold shares
+pub fn reconstruct(zkp: &ZKP, shares: &[Share]) -> Option<BigUint> {
+    shamir::reconstruct(&zkp.order, shares)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn toy_zkp() -> ZKP {
+        ZKP {
+            prime: BigUint::from(23_u32),
+            order: BigUint::from(11_u32),
+            alpha: BigUint::from(4_u32),
+            beta: BigUint::from(9_u32),
+        }
+    }
+
+    #[test]
+    fn test_feldman_toy_example() {
+        let zkp = toy_zkp();
+        let secret = BigUint::from(6_u32);
+        let (commitments, shares) = feldman_deal(&zkp, &secret, 3, 5);
+
+        assert_eq!(commitments[0], BigUint::from(2_u32));
+        assert!(shares.iter().all(|share| feldman_verify(&zkp, &commitments, share)));
+        assert_eq!(reconstruct(&zkp, &shares[1..4]), Some(secret));
+
+        let bad_share = Share { index: 2, value: (&shares[1].value + 1u32) % &zkp.order };
+        assert!(!feldman_verify(&zkp, &commitments, &bad_share));
+
+        // at index q the commitments open to C_0, so the share would be the secret itself
+        let secret_share = Share { index: 11, value: BigUint::from(6_u32) };
+        assert!(!feldman_verify(&zkp, &commitments, &secret_share));
+    }
+
+    #[test]
+    fn test_pedersen_with_1024_bit_constants() {
+        let (alpha, beta, prime, order) = ZKP::get_constants();
+        let zkp = ZKP { prime, order, alpha, beta };
+        let h = pedersen::generator(&zkp, b"server key backup");
+        let secret = ZKP::generate_random_number(&zkp.order);
+        let (commitments, shares) = pedersen_deal(&zkp, &h, &secret, 2, 3);
+
+        assert!(shares.iter().all(|share| pedersen_verify(&zkp, &h, &commitments, share)));
+        let plain: Vec<Share> = shares.iter().map(PedersenShare::to_share).collect();
+        assert_eq!(reconstruct(&zkp, &plain[1..]), Some(secret));
+
+        let mut bad_share = shares[0].clone();
+        bad_share.blinding = (&bad_share.blinding + 1u32) % &zkp.order;
+        assert!(!pedersen_verify(&zkp, &h, &commitments, &bad_share));
+    }
+}
@@ -0,0 +1,213 @@
+use num_bigint::BigUint;
+use wasm_bindgen::prelude::*;
+
+use crate::{dhparam, groups, params, ZKP};
+
+/// group parameters for a prover running in the browser
+/// every value crosses the boundary as big-endian bytes, the encoding of the `zkp_auth.proto` fields
+#[wasm_bindgen(js_name = Zkp)]
+pub struct WasmZkp {
+    zkp: ZKP,
+}
+
+/// (y1, y2) = (a^x, b^x) for `RegisterRequest`
+#[wasm_bindgen(js_name = Registration)]
+pub struct WasmRegistration {
+    y1: BigUint,
+    y2: BigUint,
+}
+
+/// (r1, r2) for `AuthenticationChallengeRequest`, keeps k on the Rust side until `solve`
+#[wasm_bindgen(js_name = Commitment)]
+pub struct WasmCommitment {
+    k: BigUint,
+    r1: BigUint,
+    r2: BigUint,
+}
+
+#[wasm_bindgen(js_class = Zkp)]
+impl WasmZkp {
+    /// the 1024-bit group of `ZKP::get_constants`, the server's default
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmZkp {
+        WasmZkp { zkp: ZKP::default() }
+    }
+
+    /// one of the groups of `groups::GROUPS`, the server's `--group <name>`
+    #[wasm_bindgen(js_name = fromGroup)]
+    pub fn from_group(name: &str) -> Result<WasmZkp, JsError> {
+        let group = groups::by_name(name).ok_or_else(|| JsError::new("unknown group name"))?;
+        Ok(WasmZkp { zkp: group.zkp()? })
+    }
+
+    /// contents of the server's `--params <file>`, in the text format or as PKCS#3 / X9.42 PEM
+    #[wasm_bindgen(js_name = fromParameters)]
+    pub fn from_parameters(text: &str) -> Result<WasmZkp, JsError> {
+        let zkp = if text.trim_start().starts_with("-----BEGIN") {
+            dhparam::from_pem(text)?
+        } else {
+            params::from_str(text)?
+        };
+        Ok(WasmZkp { zkp })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn prime(&self) -> Vec<u8> {
+        self.zkp.prime.to_bytes_be()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn order(&self) -> Vec<u8> {
+        self.zkp.order.to_bytes_be()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn alpha(&self) -> Vec<u8> {
+        self.zkp.alpha.to_bytes_be()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn beta(&self) -> Vec<u8> {
+        self.zkp.beta.to_bytes_be()
+    }
+
+    /// x from the password exactly as the command line client derives it, so both can log in the same user
+    #[wasm_bindgen(js_name = deriveSecret)]
+    pub fn derive_secret(password: &str) -> Vec<u8> {
+        BigUint::from_bytes_be(password.as_bytes()).to_bytes_be()
+    }
+
+    pub fn register(&self, secret: &[u8]) -> WasmRegistration {
+        let x = BigUint::from_bytes_be(secret);
+        WasmRegistration {
+            y1: ZKP::exponentiate(&self.zkp.alpha, &x, &self.zkp.prime),
+            y2: ZKP::exponentiate(&self.zkp.beta, &x, &self.zkp.prime),
+        }
+    }
+
+    /// fresh k < q from crypto.getRandomValues
+    pub fn commit(&self) -> WasmCommitment {
+        let (k, r1, r2) = self.zkp.commitment_with_rng(&mut rand::thread_rng());
+        WasmCommitment { k, r1, r2 }
+    }
+
+    /// s = k - c * x mod q for `AuthenticationAnswerRequest`
+    /// rejects a challenge outside [0, q), which an honest server never sends
+    pub fn solve(&self, commitment: &WasmCommitment, c: &[u8], secret: &[u8]) -> Result<Vec<u8>, JsError> {
+        let c = BigUint::from_bytes_be(c);
+        if c >= self.zkp.order {
+            return Err(JsError::new("the challenge is not below q"));
+        }
+        Ok(self.zkp.solve(&commitment.k, &c, &BigUint::from_bytes_be(secret)).to_bytes_be())
+    }
+}
+
+impl Default for WasmZkp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen(js_class = Registration)]
+impl WasmRegistration {
+    #[wasm_bindgen(getter)]
+    pub fn y1(&self) -> Vec<u8> {
+        self.y1.to_bytes_be()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn y2(&self) -> Vec<u8> {
+        self.y2.to_bytes_be()
+    }
+}
+
+#[wasm_bindgen(js_class = Commitment)]
+impl WasmCommitment {
+    #[wasm_bindgen(getter)]
+    pub fn r1(&self) -> Vec<u8> {
+        self.r1.to_bytes_be()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn r2(&self) -> Vec<u8> {
+        self.r2.to_bytes_be()
+    }
+}
+
+/// plain `cargo test` runs these natively, and under Node with
+/// `cargo test --target wasm32-unknown-unknown --no-default-features --features wasm`
+/// JsError can only be built on wasm32, so the rejection test is wasm-only
+#[cfg(test)]
+mod test {
+    use super::*;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    /// what the server does with the bytes of the proto messages
+    fn server_verifies(zkp: &WasmZkp, registration: &WasmRegistration, commitment: &WasmCommitment, c: &BigUint, s: &[u8]) -> bool {
+        let bytes = |value: Vec<u8>| BigUint::from_bytes_be(&value);
+        zkp.zkp.verify(
+            &bytes(commitment.r1()),
+            &bytes(commitment.r2()),
+            &bytes(registration.y1()),
+            &bytes(registration.y2()),
+            c,
+            &BigUint::from_bytes_be(s),
+        )
+    }
+
+    #[test]
+    fn test_toy_example() {
+        let zkp = WasmZkp::from_group("toy-23").unwrap();
+        assert_eq!((zkp.prime(), zkp.order(), zkp.alpha(), zkp.beta()), (vec![23], vec![11], vec![4], vec![9]));
+
+        let registration = zkp.register(&[6]);
+        assert_eq!((registration.y1(), registration.y2()), (vec![2], vec![3]));
+
+        let commitment = zkp.commit();
+        let c = BigUint::from(4_u32);
+        let s = zkp.solve(&commitment, &c.to_bytes_be(), &[6]).unwrap();
+        assert!(server_verifies(&zkp, &registration, &commitment, &c, &s));
+        assert!(!server_verifies(&zkp, &zkp.register(&[7]), &commitment, &c, &s));
+    }
+
+    #[test]
+    fn test_login_with_1024_bit_constants() {
+        let zkp = WasmZkp::new();
+        assert_eq!(BigUint::from_bytes_be(&zkp.prime()), ZKP::default().prime);
+
+        let secret = WasmZkp::derive_secret("correct horse");
+        assert_eq!(secret, BigUint::from_bytes_be(b"correct horse").to_bytes_be());
+        let registration = zkp.register(&secret);
+
+        let commitment = zkp.commit();
+        let c = ZKP::generate_random_number(&zkp.zkp.order);
+        let s = zkp.solve(&commitment, &c.to_bytes_be(), &secret).unwrap();
+        assert!(server_verifies(&zkp, &registration, &commitment, &c, &s));
+
+        let wrong = WasmZkp::derive_secret("battery staple");
+        let s = zkp.solve(&commitment, &c.to_bytes_be(), &wrong).unwrap();
+        assert!(!server_verifies(&zkp, &registration, &commitment, &c, &s));
+    }
+
+    #[test]
+    fn test_from_parameters() {
+        let text = params::to_string(&ZKP::default());
+        assert_eq!(WasmZkp::from_parameters(&text).unwrap().zkp, ZKP::default());
+
+        let zkp = ZKP::default();
+        let zkp = ZKP { beta: dhparam::derive_beta(&zkp.prime, &zkp.order, &zkp.alpha), ..zkp };
+        let pem = dhparam::to_pem(&zkp, dhparam::Format::X942);
+        assert_eq!(WasmZkp::from_parameters(&pem).unwrap().zkp, zkp);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    #[test]
+    fn test_rejects_unknown_groups_and_large_challenges() {
+        assert!(WasmZkp::from_group("no-such-group").is_err());
+        assert!(WasmZkp::from_parameters("p = xyz").is_err());
+
+        let zkp = WasmZkp::from_group("toy-23").unwrap();
+        assert!(zkp.solve(&zkp.commit(), &[11], &[6]).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use num_bigint::BigUint;
+use zkp_chaum_pedersen::{dlog, ZKP};
+
+const USAGE: &str = "usage: zkp-dlog <p> <q> <alpha> <y> [--method bsgs|rho|pohlig-hellman] [--budget SECONDS]
+numbers are decimal, or hex with a 0x prefix; q is the order of alpha";
+
+fn parse_integer(value: &str) -> BigUint {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(value.as_bytes(), 10),
+    };
+    parsed.unwrap_or_else(|| panic!("{} is not a number\n{}", value, USAGE))
+}
+
+fn main() {
+    let mut numbers = Vec::new();
+    let mut method = String::from("pohlig-hellman");
+    let mut budget = Duration::from_secs(10);
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--method" => method = args.next().expect(USAGE),
+            "--budget" => {
+                let seconds: f64 = args.next().and_then(|value| value.parse().ok()).expect(USAGE);
+                budget = Duration::from_secs_f64(seconds);
+            }
+            _ => numbers.push(parse_integer(&arg)),
+        }
+    }
+    let [prime, order, alpha, y]: [BigUint; 4] = match numbers.try_into() {
+        Ok(numbers) => numbers,
+        Err(_) => {
+            println!("{}", USAGE);
+            return;
+        }
+    };
+
+    let solver = match method.as_str() {
+        "bsgs" => dlog::baby_step_giant_step,
+        "rho" => dlog::pollard_rho,
+        "pohlig-hellman" => dlog::pohlig_hellman,
+        _ => {
+            println!("{}", USAGE);
+            return;
+        }
+    };
+
+    println!("Solving alpha^x = y with {} (q has {} bits, budget {:?})", method, order.bits(), budget);
+    let start = Instant::now();
+    let (sender, receiver) = mpsc::channel();
+    {
+        let (prime, order, alpha, y) = (prime.clone(), order.clone(), alpha.clone(), y.clone());
+        // the solver thread is abandoned if the budget runs out
+        thread::spawn(move || sender.send(solver(&alpha, &y, &prime, &order)));
+    }
+
+    match receiver.recv_timeout(budget) {
+        Ok(Some(x)) => {
+            assert_eq!(ZKP::exponentiate(&alpha, &x, &prime), y);
+            println!("☑ Recovered x = {} in {:?}", x, start.elapsed());
+        }
+        Ok(None) => println!("☒ y is not a power of alpha (tried for {:?})", start.elapsed()),
+        Err(_) => println!("☒ Gave up after {:?}", budget),
+    }
+}
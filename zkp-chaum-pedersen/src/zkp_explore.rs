@@ -0,0 +1,151 @@
+use num_bigint::BigUint;
+use zkp_chaum_pedersen::{number_theory, ZKP};
+
+const USAGE: &str = "usage: zkp-explore <p> [--max-table N]
+p must be a prime below 2^16; Cayley tables are printed for subgroups of order at most N (default 12)";
+
+const MAX_PRIME: u64 = 1 << 16;
+const MAX_LISTED: usize = 16;
+
+/// all divisors of the number with the given factorization, in increasing order
+fn divisors(factors: &[(BigUint, u32)]) -> Vec<BigUint> {
+    let mut divisors = vec![BigUint::from(1u32)];
+    for (factor, exponent) in factors {
+        let mut next = Vec::new();
+        for divisor in &divisors {
+            for i in 0..=*exponent {
+                next.push(divisor * factor.pow(i));
+            }
+        }
+        divisors = next;
+    }
+    divisors.sort();
+    divisors
+}
+
+/// comma separated, cut off after MAX_LISTED entries
+fn list(values: &[BigUint]) -> String {
+    let shown: Vec<String> = values.iter().take(MAX_LISTED).map(ToString::to_string).collect();
+    if values.len() > MAX_LISTED {
+        format!("{}, ... ({} in total)", shown.join(", "), values.len())
+    } else {
+        shown.join(", ")
+    }
+}
+
+fn print_cayley_table(elements: &[BigUint], prime: &BigUint) {
+    let width = prime.to_string().len() + 1;
+    print!("{:>width$} |", "*", width = width);
+    for b in elements {
+        print!("{:>width$}", b, width = width);
+    }
+    println!();
+    println!("{}", "-".repeat((elements.len() + 1) * width + 2));
+    for a in elements {
+        print!("{:>width$} |", a, width = width);
+        for b in elements {
+            print!("{:>width$}", (a * b) % prime, width = width);
+        }
+        println!();
+    }
+}
+
+fn main() {
+    let mut prime = None;
+    let mut max_table = 12;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-table" => max_table = args.next().and_then(|value| value.parse().ok()).expect(USAGE),
+            _ => prime = arg.parse::<u64>().ok(),
+        }
+    }
+    let prime = match prime {
+        Some(prime) if (3..MAX_PRIME).contains(&prime) => BigUint::from(prime),
+        _ => {
+            println!("{}", USAGE);
+            return;
+        }
+    };
+    if !number_theory::is_probable_prime(&prime, 20) {
+        println!("☒ {} is not prime", prime);
+        return;
+    }
+
+    let one = BigUint::from(1u32);
+    let group_order = &prime - &one;
+    let factors = number_theory::factorize(&group_order);
+    let factorization: Vec<String> = factors.iter().map(|(factor, exponent)| format!("{}^{}", factor, exponent)).collect();
+    println!("Z_{}^* has order p - 1 = {} = {}", prime, group_order, factorization.join(" * "));
+
+    // order of every element, so each subgroup can be read off directly
+    let elements: Vec<BigUint> = (1..MAX_PRIME).map(BigUint::from).take_while(|a| *a < prime).collect();
+    let orders: Vec<BigUint> = elements
+        .iter()
+        .map(|a| number_theory::multiplicative_order_with(a, &prime, &group_order, &factors).expect("element is not invertible"))
+        .collect();
+
+    // one cyclic subgroup for every divisor d of p - 1, generated by the elements of order d
+    for d in divisors(&factors) {
+        let generators: Vec<BigUint> = elements.iter().zip(&orders).filter(|(_, order)| **order == d).map(|(a, _)| a.clone()).collect();
+        let subgroup: Vec<BigUint> = elements
+            .iter()
+            .zip(&orders)
+            .filter(|(_, order)| (&d % *order) == BigUint::from(0u32))
+            .map(|(a, _)| a.clone())
+            .collect();
+
+        println!();
+        println!("Subgroup of order {}{}", d, if number_theory::is_probable_prime(&d, 20) { " (prime)" } else { "" });
+        println!("  elements:   {}", list(&subgroup));
+        println!("  generators: {}", list(&generators));
+        if subgroup.len() <= max_table {
+            print_cayley_table(&subgroup, &prime);
+        }
+    }
+
+    // Chaum-Pedersen in the subgroup of the largest prime order q
+    let (order, _) = factors.last().cloned().expect("p - 1 has no prime factor");
+    let generators: Vec<&BigUint> = elements.iter().zip(&orders).filter(|(_, o)| **o == order).map(|(a, _)| a).collect();
+    println!();
+    if generators.len() < 2 {
+        println!("☒ The subgroup of order {} has fewer than two generators, pick a larger p.", order);
+        return;
+    }
+    let zkp = ZKP {
+        prime: prime.clone(),
+        order: order.clone(),
+        alpha: generators[0].clone(),
+        beta: generators[1].clone(),
+    };
+    println!("Chaum-Pedersen with p = {}, q = {}, alpha = {}, beta = {}", zkp.prime, zkp.order, zkp.alpha, zkp.beta);
+
+    let x = ZKP::generate_random_number(&zkp.order);
+    let y1 = ZKP::exponentiate(&zkp.alpha, &x, &zkp.prime);
+    let y2 = ZKP::exponentiate(&zkp.beta, &x, &zkp.prime);
+    println!("  prover secret x = {}", x);
+    println!("  register:  y1 = alpha^x mod p = {},  y2 = beta^x mod p = {}", y1, y2);
+
+    let k = ZKP::generate_random_number(&zkp.order);
+    let r1 = ZKP::exponentiate(&zkp.alpha, &k, &zkp.prime);
+    let r2 = ZKP::exponentiate(&zkp.beta, &k, &zkp.prime);
+    println!("  commit:    k = {},  r1 = alpha^k mod p = {},  r2 = beta^k mod p = {}", k, r1, r2);
+
+    let c = ZKP::generate_random_number(&zkp.order);
+    println!("  challenge: c = {}", c);
+
+    let s = zkp.solve(&k, &c, &x);
+    println!("  answer:    s = k - c * x mod q = {}", s);
+
+    let check1 = (ZKP::exponentiate(&zkp.alpha, &s, &zkp.prime) * ZKP::exponentiate(&y1, &c, &zkp.prime)) % &zkp.prime;
+    let check2 = (ZKP::exponentiate(&zkp.beta, &s, &zkp.prime) * ZKP::exponentiate(&y2, &c, &zkp.prime)) % &zkp.prime;
+    println!("  verify:    alpha^s * y1^c mod p = {} (r1 = {})", check1, r1);
+    println!("             beta^s * y2^c mod p = {} (r2 = {})", check2, r2);
+
+    if zkp.verify(&r1, &r2, &y1, &y2, &c, &s) {
+        println!("☑ Proof verified.");
+    } else {
+        println!("☒ Proof rejected.");
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::Write;
+
+use zkp_chaum_pedersen::{
+    dhparam::{self, Format},
+    fips186,
+    paramgen::{self, ParamGenConfig},
+    params,
+};
+
+const USAGE: &str = "usage: zkp-paramgen [--p-bits N] [--q-bits N] [--rounds N] [--safe-prime | --fips186] [--format FORMAT] [--out FILE]
+FORMAT is text (default), x942-pem, x942-der, pkcs3-pem or pkcs3-der; pkcs3 needs --safe-prime";
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: Option<String>) -> T {
+    value
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| panic!("{} expects a number\n{}", flag, USAGE))
+}
+
+fn main() {
+    let mut config = ParamGenConfig::default();
+    let mut out = None;
+    let mut fips186 = false;
+    let mut format = String::from("text");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--p-bits" => config.p_bits = parse_number(&arg, args.next()),
+            "--q-bits" => config.q_bits = parse_number(&arg, args.next()),
+            "--rounds" => config.rounds = parse_number(&arg, args.next()),
+            "--safe-prime" => config.safe_prime = true,
+            "--fips186" => fips186 = true,
+            "--format" => format = args.next().expect(USAGE),
+            "--out" => out = Some(args.next().expect(USAGE)),
+            _ => {
+                println!("{}", USAGE);
+                return;
+            }
+        }
+    }
+
+    let der_format = match format.as_str() {
+        "text" => None,
+        "x942-pem" | "x942-der" => Some(Format::X942),
+        "pkcs3-pem" | "pkcs3-der" if config.safe_prime => Some(Format::Pkcs3),
+        _ => {
+            println!("{}", USAGE);
+            return;
+        }
+    };
+    if fips186 && der_format.is_some() {
+        println!("--fips186 needs the text format to keep the domain parameter seed\n{}", USAGE);
+        return;
+    }
+
+    let output = if fips186 {
+        eprintln!("Generating a FIPS 186-4 group with {}-bit p and {}-bit q from a random seed...", config.p_bits, config.q_bits);
+        let seed_size = config.q_bits.div_ceil(8) as usize;
+        let parameters = fips186::generate(config.p_bits, config.q_bits, seed_size, config.rounds)
+            .expect("could not generate FIPS 186-4 parameters");
+        params::to_string_with_seed(&parameters).expect("generated group has no canonical generator").into_bytes()
+    } else {
+        if config.safe_prime {
+            eprintln!("Generating a {}-bit safe prime group...", config.p_bits);
+        } else {
+            eprintln!("Generating a group with {}-bit p and {}-bit q...", config.p_bits, config.q_bits);
+        }
+        let zkp = paramgen::generate(&config);
+        params::validate(&zkp, config.rounds).expect("generated group failed validation");
+        match der_format {
+            None => params::to_string(&zkp).into_bytes(),
+            Some(der_format) => {
+                eprintln!("⚠ beta is not stored in {}, it is re-derived from p, q and alpha on import.", format);
+                if format.ends_with("pem") {
+                    dhparam::to_pem(&zkp, der_format).into_bytes()
+                } else {
+                    dhparam::to_der(&zkp, der_format)
+                }
+            }
+        }
+    };
+
+    match out {
+        Some(path) => {
+            std::fs::write(&path, output).expect("could not write the parameter file");
+            eprintln!("☑ Wrote parameters to {}, pass --params {} to the server and client.", path, path);
+        }
+        None => std::io::stdout().write_all(&output).expect("could not write to stdout"),
+    }
+}
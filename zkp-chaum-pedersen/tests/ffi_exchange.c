@@ -0,0 +1,140 @@
+// full register / commit / challenge / respond / verify exchange over the C API
+// built and run by test_c_exchange in src/lib.rs, exits non-zero on the first failed check
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "zkp_chaum_pedersen.h"
+
+#define CHECK(condition)                                                         \
+  do {                                                                           \
+    if (!(condition)) {                                                          \
+      fprintf(stderr, "☒ %s:%d: %s\n", __FILE__, __LINE__, #condition);          \
+      exit(1);                                                                   \
+    }                                                                            \
+  } while (0)
+
+// y1 and y2 as the server stores them after RegisterRequest
+struct registration {
+  uint8_t *y1, *y2;
+  size_t y1_len, y2_len;
+};
+
+static ZkpSecret *derive(const char *password) {
+  ZkpSecret *secret = NULL;
+  CHECK(zkp_secret_derive((const uint8_t *)password, strlen(password), &secret) == ZKP_STATUS_OK);
+  return secret;
+}
+
+static struct registration register_user(const ZkpContext *context, const char *password) {
+  size_t element_size = zkp_element_size(context);
+  struct registration user = {malloc(element_size), malloc(element_size), element_size, element_size};
+  ZkpSecret *secret = derive(password);
+  CHECK(zkp_register(context, secret, user.y1, &user.y1_len, user.y2, &user.y2_len) == ZKP_STATUS_OK);
+  zkp_secret_free(secret);
+  return user;
+}
+
+// one login of a prover who knows `password`, returns the verifier's status
+static ZkpStatus login(const ZkpContext *context, const struct registration *user, const char *password) {
+  size_t element_size = zkp_element_size(context);
+  size_t scalar_size = zkp_scalar_size(context);
+  uint8_t *r1 = malloc(element_size), *r2 = malloc(element_size);
+  uint8_t *c = malloc(scalar_size), *s = malloc(scalar_size);
+  size_t r1_len = element_size, r2_len = element_size, c_len = scalar_size, s_len = scalar_size;
+
+  // prover commits, verifier challenges, prover responds
+  ZkpCommitment *commitment = NULL;
+  CHECK(zkp_commit(context, &commitment, r1, &r1_len, r2, &r2_len) == ZKP_STATUS_OK);
+  CHECK(zkp_challenge(context, c, &c_len) == ZKP_STATUS_OK);
+  ZkpSecret *secret = derive(password);
+  CHECK(zkp_respond(context, commitment, secret, c, c_len, s, &s_len) == ZKP_STATUS_OK);
+
+  ZkpStatus status = zkp_verify(context, user->y1, user->y1_len, user->y2, user->y2_len, r1, r1_len, r2,
+                                r2_len, c, c_len, s, s_len);
+
+  zkp_secret_free(secret);
+  zkp_commitment_free(commitment);
+  free(r1);
+  free(r2);
+  free(c);
+  free(s);
+  return status;
+}
+
+static void exchange(const char *name, ZkpContext *context) {
+  struct registration user = register_user(context, "correct horse");
+  CHECK(login(context, &user, "correct horse") == ZKP_STATUS_OK);
+  CHECK(login(context, &user, "battery staple") == ZKP_STATUS_REJECTED);
+  printf("☑ %s: %zu-byte elements, the right password logs in and a wrong one is rejected\n", name,
+         zkp_element_size(context));
+  free(user.y1);
+  free(user.y2);
+  zkp_context_free(context);
+}
+
+static void toy_example(void) {
+  ZkpContext *context = NULL;
+  CHECK(zkp_context_new_group("toy-23", &context) == ZKP_STATUS_OK);
+  CHECK(zkp_element_size(context) == 1 && zkp_scalar_size(context) == 1);
+
+  // x = 6 gives (y1, y2) = (2, 3), k = 7 and c = 4 give s = 5
+  ZkpSecret *secret = NULL;
+  uint8_t x = 6;
+  CHECK(zkp_secret_derive(&x, 1, &secret) == ZKP_STATUS_OK);
+  uint8_t y1, y2;
+  size_t y1_len = 1, y2_len = 1;
+  CHECK(zkp_register(context, secret, &y1, &y1_len, &y2, &y2_len) == ZKP_STATUS_OK);
+  CHECK(y1 == 2 && y2 == 3);
+
+  uint8_t r1 = 8, r2 = 4, c = 4, s = 5;
+  CHECK(zkp_verify(context, &y1, 1, &y2, 1, &r1, 1, &r2, 1, &c, 1, &s, 1) == ZKP_STATUS_OK);
+  s = 6;
+  CHECK(zkp_verify(context, &y1, 1, &y2, 1, &r1, 1, &r2, 1, &c, 1, &s, 1) == ZKP_STATUS_REJECTED);
+  zkp_secret_free(secret);
+
+  exchange("toy-23", context);
+}
+
+static void error_codes(void) {
+  ZkpContext *context = NULL;
+  CHECK(zkp_context_new_group("no-such-group", &context) == ZKP_STATUS_UNKNOWN_GROUP && context == NULL);
+  CHECK(zkp_context_new_params("p = xyz", &context) == ZKP_STATUS_INVALID_PARAMETERS);
+  CHECK(zkp_context_new_group(NULL, &context) == ZKP_STATUS_NULL_POINTER);
+  CHECK(zkp_context_new_default(NULL) == ZKP_STATUS_NULL_POINTER);
+
+  CHECK(zkp_context_new_default(&context) == ZKP_STATUS_OK);
+  uint8_t small[4];
+  size_t small_len = sizeof small;
+  CHECK(zkp_challenge(context, small, &small_len) == ZKP_STATUS_BUFFER_TOO_SMALL);
+  CHECK(small_len > sizeof small && small_len <= zkp_scalar_size(context));
+
+  // c = q is not a valid challenge
+  ZkpContext *toy = NULL;
+  CHECK(zkp_context_new_group("toy-23", &toy) == ZKP_STATUS_OK);
+  uint8_t r[1], q = 11, s[1];
+  size_t r1_len = 1, r2_len = 1, s_len = 1;
+  ZkpCommitment *commitment = NULL;
+  CHECK(zkp_commit(toy, &commitment, r, &r1_len, r, &r2_len) == ZKP_STATUS_OK);
+  ZkpSecret *secret = derive("x");
+  CHECK(zkp_respond(toy, commitment, secret, &q, 1, s, &s_len) == ZKP_STATUS_INVALID_CHALLENGE);
+
+  zkp_secret_free(secret);
+  zkp_commitment_free(commitment);
+  zkp_context_free(toy);
+  zkp_context_free(context);
+  zkp_context_free(NULL);
+  printf("☑ errors are reported as status codes\n");
+}
+
+int main(void) {
+  toy_example();
+
+  ZkpContext *context = NULL;
+  CHECK(zkp_context_new_default(&context) == ZKP_STATUS_OK);
+  exchange("rfc5114-1024-160", context);
+
+  error_codes();
+  return 0;
+}